@@ -14,6 +14,56 @@ use rand::Rng;              // Librería externa. Genera número aleatorios.
  * - Instalar con "cargo build"
  */ 
 
+// Rango válido para el número secreto y para los intentos.
+const MIN: u32 = 1;
+const MAX: u32 = 100;
+
+/* Cada línea que escribe el usuario se interpreta como un comando o como un intento.
+ * Si no es ninguno de los dos, se devuelve un InputError en vez de ignorarla.
+ */
+enum Command {
+    Guess(u32),
+    Quit,
+    Hint,
+    History,
+}
+
+enum InputError {
+    Empty,
+    NotANumber(String),
+    OutOfRange(String),
+}
+
+fn parse_input(input: &str) -> Result<Command, InputError> {
+    let input = input.trim();
+
+    match input.to_lowercase().as_str() {
+        "" => return Err(InputError::Empty),
+        "quit" | "exit" => return Ok(Command::Quit),
+        "hint" => return Ok(Command::Hint),
+        "history" => return Ok(Command::History),
+        _ => {}
+    }
+
+    // Se parsea como i64 para distinguir un número fuera de rango (ej: -5, 500)
+    // de un texto que no es un número. Un número que ni siquiera cabe en un i64
+    // (ej: 99999999999999999999) también está fuera de rango.
+    match input.parse::<i64>() {
+        Ok(num) if num < MIN as i64 || num > MAX as i64 => {
+            Err(InputError::OutOfRange(input.to_string()))
+        },
+        Ok(num) => Ok(Command::Guess(num as u32)),
+        Err(_) if is_integer(input) => Err(InputError::OutOfRange(input.to_string())),
+        Err(_) => Err(InputError::NotANumber(input.to_string())),
+    }
+}
+
+// Un signo opcional seguido solo de dígitos.
+fn is_integer(input: &str) -> bool {
+    let digits = input.strip_prefix(['+', '-']).unwrap_or(input);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
 /* El compilador da error si no se usa una variable. 
  * Para que la ignore, se pone un '_' antes del nombre.
  * 
@@ -29,13 +79,19 @@ use rand::Rng;              // Librería externa. Genera número aleatorios.
 fn main() {
     println!("Guess the number!");
     
-    let secret_number = rand::thread_rng().gen_range(1, 101);
+    let secret_number = rand::thread_rng().gen_range(MIN, MAX + 1);
     
     // print con un placeholder para la variable.
     println!("The secret number is: {}", secret_number);
+    println!("Commands: 'hint', 'history', 'quit'");
+
+    // Intentos anteriores y límites conocidos del número secreto (para 'hint').
+    let mut history: Vec<u32> = Vec::new();
+    let mut low = MIN;
+    let mut high = MAX;
 
     loop {
-        println!("Please input your guess!");
+        println!("Please input your guess ({}-{})!", MIN, MAX);
 
         // Función asociativa de String (en otros lenguajes, 'static'). 
         // String es el tipo de dato y new() es la función que lo crea.
@@ -43,24 +99,83 @@ fn main() {
 
         // &mut guess: referencia a una variable mutable.
         // Sirve como buffer y le añade contenido sin tomar ownership.
-        io::stdin()
+        // read_line devuelve el número de bytes leídos: 0 significa EOF (ej: Ctrl+D).
+        let bytes = io::stdin()
             .read_line(&mut guess)
             .expect("Failed to read line");
 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,    // Devuelve el valor.
-            Err(_) => continue,     // '_' atrapa todos los valores.
+        if bytes == 0 {
+            println!("\nNo more input. The secret number was {}.", secret_number);
+            break;
+        }
+
+        let guess: u32 = match parse_input(&guess) {
+            Ok(Command::Guess(num)) => num,    // Devuelve el valor.
+            Ok(Command::Quit) => {
+                println!("Bye! The secret number was {}.", secret_number);
+                break;
+            },
+            Ok(Command::Hint) => {
+                println!("The secret number is between {} and {}.", low, high);
+                continue;
+            },
+            Ok(Command::History) => {
+                if history.is_empty() {
+                    println!("No guesses yet.");
+                } else {
+                    let list: Vec<String> = history.iter().map(|n| n.to_string()).collect();
+                    println!("Your guesses: {}", list.join(", "));
+                }
+                continue;
+            },
+            Err(InputError::Empty) => continue,
+            Err(InputError::NotANumber(text)) => {
+                println!("{}", format!("'{}' is not a number.", text).yellow());
+                continue;
+            },
+            Err(InputError::OutOfRange(text)) => {
+                println!("{}", format!("{} is out of range ({}-{}).", text, MIN, MAX).yellow());
+                continue;
+            },
         };
 
+        if history.contains(&guess) {
+            println!("{}", format!("You already guessed {}.", guess).yellow());
+            continue;
+        }
+        history.push(guess);
+
         println!("You guessed: {}", guess);
         
         match guess.cmp(&secret_number) {
-            Ordering::Less => println!("{}", "Too small!".red()),
-            Ordering::Greater => println!("{}", "Too big!".red()),
+            Ordering::Less => {
+                println!("{}", "Too small!".red());
+                low = low.max(guess + 1);
+            },
+            Ordering::Greater => {
+                println!("{}", "Too big!".red());
+                high = high.min(guess - 1);
+            },
             Ordering::Equal => {
-                println!("{}", "You win!".green());
+                println!("{}", format!("You win in {} guesses!", history.len()).green());
                 break;
             },
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_outside_the_range() {
+        assert!(matches!(parse_input("42\n"), Ok(Command::Guess(42))));
+        for input in ["0", "-5", "500", "99999999999999999999", "-99999999999999999999"] {
+            assert!(matches!(parse_input(input), Err(InputError::OutOfRange(text)) if text == input));
+        }
+        for input in ["abc", "4.5", "-", "1e3"] {
+            assert!(matches!(parse_input(input), Err(InputError::NotANumber(_))));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::VecDeque;
+
+// super: referencia al módulo padre (crate)
+use super::front_of_house::serving::OrderId;
+
+pub mod simulation;
+
+/// The queue of orders waiting to be cooked, first come first served.
+#[derive(Debug, Clone, Default)]
+pub struct Kitchen {
+    queue: VecDeque<OrderId>,
+}
+
+impl Kitchen {
+    pub fn new() -> Kitchen {
+        Kitchen::default()
+    }
+
+    /// Adds a new order to the end of the queue.
+    pub fn receive_order(&mut self, id: OrderId) {
+        self.queue.push_back(id);
+    }
+
+    /// Cooks the next order in the queue and returns it.
+    pub fn cook_order(&mut self) -> Option<OrderId> {
+        self.queue.pop_front()
+    }
+
+    /// Puts an order that came back wrong at the front of the queue, so it's
+    /// the next one cooked.
+    pub fn fix_incorrect_order(&mut self, id: OrderId) {
+        self.queue.push_front(id);
+    }
+
+    /// Orders waiting to be cooked, next one first.
+    pub fn queue(&self) -> impl Iterator<Item = &OrderId> {
+        self.queue.iter()
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::front_of_house::hosting::TableNumber;
+use crate::front_of_house::serving::OrderId;
+use crate::RestaurantError;
+
+/* Simulación de la cocina con hilos:
+ *
+ *   tickets ──> jefe de cocina ──(un canal por cocinero)──> cocineros
+ *                     │                                        │
+ *                     └───────────> expedidor <────────────────┘
+ *
+ * El jefe de cocina reparte cada plato al cocinero que antes quede libre,
+ * así que los tiempos se calculan sobre un reloj simulado y no dependen de
+ * cómo el sistema operativo planifique los hilos: el resultado es siempre el
+ * mismo. Los cocineros "cocinan" (esperando según el Pace) y mandan el plato
+ * al expedidor, que junta los platos de cada pedido y lo da por terminado
+ * cuando están todos.
+ *
+ * El expedidor reconoce los pedidos por su OrderId, así que los tickets se
+ * revisan antes de arrancar los hilos para que no haya dos con el mismo.
+ */
+
+/// A dish of an order and how long it takes to prepare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dish {
+    pub name: String,
+    pub prep: Duration,
+}
+
+impl Dish {
+    pub fn new(name: &str, prep: Duration) -> Dish {
+        Dish {
+            name: String::from(name),
+            prep,
+        }
+    }
+}
+
+/// An order as the kitchen sees it. `placed_at` is the simulated time since
+/// the kitchen opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ticket {
+    pub order: OrderId,
+    pub table: TableNumber,
+    pub placed_at: Duration,
+    pub dishes: Vec<Dish>,
+}
+
+/// A dish once cooked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plate {
+    pub order: OrderId,
+    pub dish: String,
+    /// The cook that prepared it, from `0`.
+    pub cook: usize,
+    pub started: Duration,
+    pub ready: Duration,
+}
+
+/// An order with every dish ready to take to the table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedOrder {
+    pub order: OrderId,
+    pub table: TableNumber,
+    pub placed_at: Duration,
+    pub ready_at: Duration,
+    /// In the same order as the dishes of the ticket.
+    pub plates: Vec<Plate>,
+}
+
+impl CompletedOrder {
+    /// Time from the order being placed until its last dish is ready.
+    pub fn ticket_time(&self) -> Duration {
+        self.ready_at - self.placed_at
+    }
+}
+
+/// How fast the cook threads go through their work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pace {
+    /// Cooking takes no real time at all.
+    #[default]
+    Simulated,
+    /// Cooks really wait for the prep time, divided by `speedup`.
+    RealTime { speedup: u32 },
+}
+
+impl Pace {
+    fn cook(self, prep: Duration) {
+        if let Pace::RealTime { speedup } = self {
+            thread::sleep(prep / speedup.max(1));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    pub orders: usize,
+    pub dishes: usize,
+    pub average_ticket_time: Duration,
+    pub longest_ticket_time: Duration,
+    /// From the first order placed until the last one is ready.
+    pub span: Duration,
+    /// Completed orders per hour during the span.
+    pub throughput: f64,
+    /// Fraction of the span each cook spent cooking.
+    pub utilization: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Completed orders, first ready first.
+    pub orders: Vec<CompletedOrder>,
+    pub metrics: Metrics,
+}
+
+/// A kitchen with a number of cooks working in parallel threads.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use ch07_02_restaurant::back_of_house::simulation::{Dish, KitchenSimulation, Ticket};
+///
+/// let minutes = |m: u64| Duration::from_secs(m * 60);
+/// let tickets = vec![
+///     Ticket {
+///         order: 1,
+///         table: 4,
+///         placed_at: minutes(0),
+///         dishes: vec![Dish::new("Soup", minutes(5)), Dish::new("Salmon", minutes(15))],
+///     },
+///     Ticket {
+///         order: 2,
+///         table: 7,
+///         placed_at: minutes(2),
+///         dishes: vec![Dish::new("Flan", minutes(3))],
+///     },
+/// ];
+///
+/// let report = KitchenSimulation::new(2).run(tickets).unwrap();
+/// // El flan espera a que el primer cocinero acabe la sopa
+/// assert_eq!(vec![2, 1], report.orders.iter().map(|o| o.order).collect::<Vec<_>>());
+/// assert_eq!(minutes(6), report.orders[0].ticket_time());
+/// assert_eq!(minutes(15), report.metrics.longest_ticket_time);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KitchenSimulation {
+    cooks: usize,
+    pace: Pace,
+}
+
+// Lo que le llega al expedidor: el jefe de cocina le avisa de cada pedido y
+// los cocineros le mandan los platos, en cualquier orden.
+enum Pass {
+    Ticket {
+        order: OrderId,
+        table: TableNumber,
+        placed_at: Duration,
+        dishes: Vec<String>,
+    },
+    Plate(Plate),
+}
+
+struct Assignment {
+    order: OrderId,
+    dish: Dish,
+    started: Duration,
+}
+
+impl KitchenSimulation {
+    /// # Panics
+    ///
+    /// Panics if there are no cooks.
+    pub fn new(cooks: usize) -> KitchenSimulation {
+        assert!(cooks > 0, "a kitchen needs at least one cook");
+        KitchenSimulation {
+            cooks,
+            pace: Pace::Simulated,
+        }
+    }
+
+    pub fn with_pace(self, pace: Pace) -> KitchenSimulation {
+        KitchenSimulation { pace, ..self }
+    }
+
+    /// Cooks every ticket and returns the completed orders and the metrics.
+    ///
+    /// Fails with [`RestaurantError::DuplicateOrder`], before cooking
+    /// anything, if two tickets have the same order id.
+    pub fn run(&self, mut tickets: Vec<Ticket>) -> Result<Report, RestaurantError> {
+        let mut ids = HashSet::new();
+        if let Some(ticket) = tickets.iter().find(|ticket| !ids.insert(ticket.order)) {
+            return Err(RestaurantError::DuplicateOrder(ticket.order));
+        }
+        tickets.sort_by_key(|ticket| ticket.placed_at);
+
+        let (pass_tx, pass_rx) = mpsc::channel();
+        let expediter = thread::spawn(move || expedite(pass_rx));
+
+        let mut stations = Vec::with_capacity(self.cooks);
+        let mut cooks = Vec::with_capacity(self.cooks);
+        for cook in 0..self.cooks {
+            let (tx, rx) = mpsc::channel();
+            let pass = pass_tx.clone();
+            let pace = self.pace;
+            cooks.push(thread::spawn(move || work(cook, rx, pass, pace)));
+            stations.push(tx);
+        }
+
+        let busy = self.dispatch(tickets, &stations, &pass_tx);
+
+        // Al cerrar los canales los cocineros terminan, y con ellos el expedidor
+        drop(stations);
+        drop(pass_tx);
+        for cook in cooks {
+            cook.join().expect("a cook thread panicked");
+        }
+        let orders = expediter.join().expect("the expediter thread panicked");
+
+        let metrics = metrics(&orders, &busy);
+        Ok(Report { orders, metrics })
+    }
+
+    // Reparte los platos y devuelve cuánto tiempo cocina cada cocinero.
+    fn dispatch(
+        &self,
+        tickets: Vec<Ticket>,
+        stations: &[Sender<Assignment>],
+        pass: &Sender<Pass>,
+    ) -> Vec<Duration> {
+        let mut free_at = vec![Duration::ZERO; self.cooks];
+        let mut busy = vec![Duration::ZERO; self.cooks];
+
+        for ticket in tickets {
+            pass.send(Pass::Ticket {
+                order: ticket.order,
+                table: ticket.table,
+                placed_at: ticket.placed_at,
+                dishes: ticket.dishes.iter().map(|dish| dish.name.clone()).collect(),
+            })
+            .expect("the expediter is waiting for tickets");
+
+            for dish in ticket.dishes {
+                let cook = (0..self.cooks)
+                    .min_by_key(|&cook| (free_at[cook].max(ticket.placed_at), cook))
+                    .expect("there's at least one cook");
+                let started = free_at[cook].max(ticket.placed_at);
+                free_at[cook] = started + dish.prep;
+                busy[cook] += dish.prep;
+
+                stations[cook]
+                    .send(Assignment {
+                        order: ticket.order,
+                        dish,
+                        started,
+                    })
+                    .expect("cooks are waiting for dishes");
+            }
+        }
+
+        busy
+    }
+}
+
+fn work(cook: usize, station: Receiver<Assignment>, pass: Sender<Pass>, pace: Pace) {
+    for assignment in station {
+        pace.cook(assignment.dish.prep);
+        let plate = Plate {
+            order: assignment.order,
+            ready: assignment.started + assignment.dish.prep,
+            dish: assignment.dish.name,
+            cook,
+            started: assignment.started,
+        };
+        pass.send(Pass::Plate(plate))
+            .expect("the expediter is waiting for plates");
+    }
+}
+
+fn expedite(pass: Receiver<Pass>) -> Vec<CompletedOrder> {
+    let mut tickets: HashMap<OrderId, (TableNumber, Duration, Vec<String>)> = HashMap::new();
+    let mut plates: HashMap<OrderId, Vec<Plate>> = HashMap::new();
+    let mut completed = Vec::new();
+
+    for message in pass {
+        let order = match message {
+            Pass::Ticket {
+                order,
+                table,
+                placed_at,
+                dishes,
+            } => {
+                tickets.insert(order, (table, placed_at, dishes));
+                order
+            }
+            Pass::Plate(plate) => {
+                let order = plate.order;
+                plates.entry(order).or_default().push(plate);
+                order
+            }
+        };
+
+        let cooked = plates.get(&order).map_or(0, Vec::len);
+        let ready = match tickets.get(&order) {
+            Some((_, _, dishes)) => dishes.len() == cooked,
+            None => false,
+        };
+        if ready {
+            let (table, placed_at, dishes) = tickets.remove(&order).unwrap();
+            let mut cooked = plates.remove(&order).unwrap_or_default();
+            cooked.sort_by_key(|plate| (plate.started, plate.cook));
+
+            // Los platos se colocan en el mismo orden que en el ticket
+            let mut in_order = Vec::with_capacity(dishes.len());
+            for dish in &dishes {
+                let index = cooked.iter().position(|plate| &plate.dish == dish).unwrap();
+                in_order.push(cooked.swap_remove(index));
+            }
+
+            completed.push(CompletedOrder {
+                order,
+                table,
+                placed_at,
+                ready_at: in_order
+                    .iter()
+                    .map(|plate| plate.ready)
+                    .max()
+                    .unwrap_or(placed_at),
+                plates: in_order,
+            });
+        }
+    }
+
+    completed.sort_by_key(|order| (order.ready_at, order.order));
+    completed
+}
+
+fn metrics(orders: &[CompletedOrder], busy: &[Duration]) -> Metrics {
+    let dishes = orders.iter().map(|order| order.plates.len()).sum();
+    let total: Duration = orders.iter().map(CompletedOrder::ticket_time).sum();
+    let average_ticket_time = if orders.is_empty() {
+        Duration::ZERO
+    } else {
+        total / orders.len() as u32
+    };
+    let longest_ticket_time = orders
+        .iter()
+        .map(CompletedOrder::ticket_time)
+        .max()
+        .unwrap_or(Duration::ZERO);
+
+    let first = orders.iter().map(|order| order.placed_at).min();
+    let last = orders.iter().map(|order| order.ready_at).max();
+    let span = match (first, last) {
+        (Some(first), Some(last)) => last - first,
+        _ => Duration::ZERO,
+    };
+
+    let hours = span.as_secs_f64() / 3600.0;
+    let throughput = if hours > 0.0 {
+        orders.len() as f64 / hours
+    } else {
+        0.0
+    };
+    let utilization = busy
+        .iter()
+        .map(|busy| {
+            if span.is_zero() {
+                0.0
+            } else {
+                busy.as_secs_f64() / span.as_secs_f64()
+            }
+        })
+        .collect();
+
+    Metrics {
+        orders: orders.len(),
+        dishes,
+        average_ticket_time,
+        longest_ticket_time,
+        span,
+        throughput,
+        utilization,
+    }
+}
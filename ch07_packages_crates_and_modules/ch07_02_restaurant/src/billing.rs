@@ -0,0 +1,279 @@
+use std::fmt;
+
+use crate::front_of_house::hosting::TableNumber;
+use crate::front_of_house::serving::{Bill, OrderItem};
+
+/* Todas las cantidades son céntimos en enteros (u64) y los porcentajes son
+ * puntos básicos (1% = 100), así que no hay errores de coma flotante.
+ * Al aplicar un porcentaje se redondea al céntimo más cercano (0.5 hacia arriba)
+ * y al repartir una cantidad los céntimos que sobran se asignan uno a uno,
+ * de forma que las partes siempre suman exactamente el total.
+ */
+
+/// A percentage, stored exactly in basis points (hundredths of a percent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rate {
+    basis_points: u32,
+}
+
+impl Rate {
+    pub const ZERO: Rate = Rate { basis_points: 0 };
+
+    /// A whole percentage, like `Rate::percent(10)` for 10%. Percentages too
+    /// big to store in basis points stay at the largest rate.
+    pub fn percent(percent: u32) -> Rate {
+        Rate {
+            basis_points: percent.saturating_mul(100),
+        }
+    }
+
+    /// A percentage in hundredths, like `Rate::basis_points(825)` for 8.25%.
+    pub fn basis_points(basis_points: u32) -> Rate {
+        Rate { basis_points }
+    }
+
+    /// The rate applied to an amount of cents, rounded to the nearest cent.
+    pub fn of(self, cents: u64) -> u64 {
+        let exact = cents as u128 * self.basis_points as u128;
+        ((exact + 5_000) / 10_000) as u64
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let whole = self.basis_points / 100;
+        let hundredths = self.basis_points % 100;
+        if hundredths == 0 {
+            write!(f, "{}%", whole)
+        } else {
+            let decimals = format!("{:02}", hundredths);
+            write!(f, "{}.{}%", whole, decimals.trim_end_matches('0'))
+        }
+    }
+}
+
+/// Money taken off the subtotal, before tax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Discount {
+    Percent(Rate),
+    /// A fixed amount of cents. It never makes the subtotal negative.
+    Fixed(u64),
+}
+
+/// Tip for the staff, calculated on the subtotal before discounts and tax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tip {
+    #[default]
+    None,
+    Percent(Rate),
+    Fixed(u64),
+}
+
+/// How a bill is charged: the tax rate, an optional discount and the tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Charges {
+    pub tax: Rate,
+    pub discount: Option<Discount>,
+    pub tip: Tip,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BillingError {
+    /// A bill can't be split among zero diners.
+    NoDiners,
+    /// The split doesn't say who pays each item of the receipt.
+    WrongItemCount { expected: usize, found: usize },
+    /// An item isn't assigned to anyone.
+    UnassignedItem(usize),
+    /// An item is assigned to a diner that doesn't exist.
+    UnknownDiner(usize),
+}
+
+impl fmt::Display for BillingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BillingError::NoDiners => write!(f, "there must be at least one diner"),
+            BillingError::WrongItemCount { expected, found } => {
+                write!(f, "expected {} items to split, found {}", expected, found)
+            }
+            BillingError::UnassignedItem(item) => write!(f, "item {} has no diner", item),
+            BillingError::UnknownDiner(diner) => write!(f, "there's no diner {}", diner),
+        }
+    }
+}
+
+impl std::error::Error for BillingError {}
+
+/// A bill with every charge applied. Amounts are in cents.
+///
+/// # Examples
+/// ```
+/// use ch07_02_restaurant::billing::{Charges, Discount, Rate, Receipt, Tip};
+/// use ch07_02_restaurant::front_of_house::serving::{Bill, OrderItem};
+///
+/// let bill = Bill {
+///     table: 1,
+///     orders: vec![1],
+///     items: vec![OrderItem::new("Soup", 450), OrderItem::new("Salmon", 1650)],
+/// };
+/// let charges = Charges {
+///     tax: Rate::percent(10),
+///     discount: Some(Discount::Fixed(100)),
+///     tip: Tip::Percent(Rate::percent(15)),
+/// };
+///
+/// let receipt = Receipt::new(&bill, &charges);
+/// assert_eq!(2100, receipt.subtotal);
+/// assert_eq!(200, receipt.tax);
+/// assert_eq!(315, receipt.tip);
+/// assert_eq!(2515, receipt.total);
+/// assert_eq!(vec![1258, 1257], receipt.split_evenly(2).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub table: TableNumber,
+    pub items: Vec<OrderItem>,
+    pub tax_rate: Rate,
+    pub subtotal: u64,
+    pub discount: u64,
+    pub tax: u64,
+    pub tip: u64,
+    pub total: u64,
+}
+
+impl Receipt {
+    pub fn new(bill: &Bill, charges: &Charges) -> Receipt {
+        let subtotal = bill.total();
+
+        let discount = match charges.discount {
+            Some(Discount::Percent(rate)) => rate.of(subtotal),
+            Some(Discount::Fixed(cents)) => cents,
+            None => 0,
+        }
+        .min(subtotal);
+
+        let tax = charges.tax.of(subtotal - discount);
+
+        let tip = match charges.tip {
+            Tip::None => 0,
+            Tip::Percent(rate) => rate.of(subtotal),
+            Tip::Fixed(cents) => cents,
+        };
+
+        Receipt {
+            table: bill.table,
+            items: bill.items.clone(),
+            tax_rate: charges.tax,
+            subtotal,
+            discount,
+            tax,
+            tip,
+            total: subtotal - discount + tax + tip,
+        }
+    }
+
+    /// Splits the total among `diners` in equal parts. When it can't be
+    /// split exactly, the first diners pay one more cent.
+    pub fn split_evenly(&self, diners: usize) -> Result<Vec<u64>, BillingError> {
+        if diners == 0 {
+            return Err(BillingError::NoDiners);
+        }
+        Ok(allocate(self.total, &vec![1; diners]))
+    }
+
+    /// Splits the total by what each diner had. `shares[i]` lists the diners
+    /// (from `0` to `diners - 1`) that shared item `i`, who split its price
+    /// evenly. Discount, tax and tip are split in proportion to that.
+    pub fn split_by_item(
+        &self,
+        diners: usize,
+        shares: &[Vec<usize>],
+    ) -> Result<Vec<u64>, BillingError> {
+        if diners == 0 {
+            return Err(BillingError::NoDiners);
+        }
+        if shares.len() != self.items.len() {
+            return Err(BillingError::WrongItemCount {
+                expected: self.items.len(),
+                found: shares.len(),
+            });
+        }
+
+        let mut consumed = vec![0; diners];
+        for (index, (item, sharing)) in self.items.iter().zip(shares).enumerate() {
+            if sharing.is_empty() {
+                return Err(BillingError::UnassignedItem(index));
+            }
+            if let Some(&diner) = sharing.iter().find(|&&diner| diner >= diners) {
+                return Err(BillingError::UnknownDiner(diner));
+            }
+
+            let parts = allocate(item.price, &vec![1; sharing.len()]);
+            for (&diner, part) in sharing.iter().zip(parts) {
+                consumed[diner] += part;
+            }
+        }
+
+        Ok(allocate(self.total, &consumed))
+    }
+
+    /// The receipt as text, ready to print.
+    pub fn render(&self) -> String {
+        const WIDTH: usize = 32;
+        let line = |label: &str, amount: String| format!("{:<20}{:>12}\n", label, amount);
+        let separator = format!("{}\n", "-".repeat(WIDTH));
+
+        let mut text = format!("Table {}\n", self.table);
+        for item in &self.items {
+            text += &line(&item.name, format_cents(item.price));
+        }
+        text += &separator;
+        text += &line("Subtotal", format_cents(self.subtotal));
+        if self.discount > 0 {
+            text += &line("Discount", format!("-{}", format_cents(self.discount)));
+        }
+        text += &line(&format!("Tax ({})", self.tax_rate), format_cents(self.tax));
+        if self.tip > 0 {
+            text += &line("Tip", format_cents(self.tip));
+        }
+        text += &separator;
+        text += &line("Total", format_cents(self.total));
+        text
+    }
+}
+
+/// Formats an amount of cents with two decimals, like `"12.05"`.
+pub fn format_cents(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+/* Reparte `total` en proporción a `weights` con el método del mayor resto:
+ * cada parte recibe la parte entera de su proporción y los céntimos que faltan
+ * van a las partes con mayor resto (en empate, a la primera).
+ * Si todos los pesos son 0, se reparte a partes iguales.
+ */
+fn allocate(total: u64, weights: &[u64]) -> Vec<u64> {
+    let sum: u128 = weights.iter().map(|&w| w as u128).sum();
+    if sum == 0 {
+        return allocate(total, &vec![1; weights.len()]);
+    }
+
+    let exact: Vec<(u64, u128)> = weights
+        .iter()
+        .map(|&w| {
+            let scaled = total as u128 * w as u128;
+            ((scaled / sum) as u64, scaled % sum)
+        })
+        .collect();
+
+    let mut parts: Vec<u64> = exact.iter().map(|&(part, _)| part).collect();
+    let missing = total - parts.iter().sum::<u64>();
+
+    let mut by_remainder: Vec<usize> = (0..parts.len()).collect();
+    by_remainder.sort_by(|&a, &b| exact[b].1.cmp(&exact[a].1));
+    for &index in by_remainder.iter().take(missing as usize) {
+        parts[index] += 1;
+    }
+
+    parts
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+use crate::front_of_house::hosting::TableNumber;
+use crate::front_of_house::reservations::ReservationId;
+use crate::front_of_house::serving::OrderId;
+
+/// Everything that can go wrong while running the restaurant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestaurantError {
+    /// There's no table big enough for the party.
+    PartyTooLarge(u32),
+    /// A party must have at least one person.
+    EmptyParty,
+    UnknownTable(TableNumber),
+    /// The table has no party sitting at it.
+    TableNotOccupied(TableNumber),
+    /// The table already has a party sitting at it.
+    TableOccupied(TableNumber),
+    UnknownOrder(OrderId),
+    /// Two orders were given the same id.
+    DuplicateOrder(OrderId),
+    /// An order must have at least one item.
+    EmptyOrder,
+    /// The order isn't in the state the operation needs.
+    WrongOrderStatus(OrderId),
+    /// The table has orders that haven't been served yet.
+    OrdersPending(TableNumber),
+    /// The table has nothing to pay.
+    NothingToPay(TableNumber),
+    /// There aren't enough free tables at that time, even putting them together.
+    NoTableAvailable,
+    UnknownReservation(ReservationId),
+    /// The reservation isn't in the state the operation needs.
+    WrongReservationStatus(ReservationId),
+    /// Reservations can't start before the current time.
+    InThePast,
+    /// Reservations must start at the beginning of a time slot.
+    NotOnSlot,
+}
+
+impl fmt::Display for RestaurantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RestaurantError::PartyTooLarge(size) => {
+                write!(f, "no table can seat a party of {}", size)
+            }
+            RestaurantError::EmptyParty => write!(f, "a party needs at least one person"),
+            RestaurantError::UnknownTable(table) => write!(f, "there's no table {}", table),
+            RestaurantError::TableNotOccupied(table) => write!(f, "table {} is free", table),
+            RestaurantError::TableOccupied(table) => write!(f, "table {} is taken", table),
+            RestaurantError::UnknownOrder(id) => write!(f, "there's no order {}", id),
+            RestaurantError::DuplicateOrder(id) => write!(f, "there's more than one order {}", id),
+            RestaurantError::EmptyOrder => write!(f, "an order needs at least one item"),
+            RestaurantError::WrongOrderStatus(id) => {
+                write!(f, "order {} can't do that in its current status", id)
+            }
+            RestaurantError::OrdersPending(table) => {
+                write!(f, "table {} has orders that haven't been served", table)
+            }
+            RestaurantError::NothingToPay(table) => write!(f, "table {} has nothing to pay", table),
+            RestaurantError::NoTableAvailable => write!(f, "there's no table available"),
+            RestaurantError::UnknownReservation(id) => write!(f, "there's no reservation {}", id),
+            RestaurantError::WrongReservationStatus(id) => {
+                write!(f, "reservation {} can't do that in its current status", id)
+            }
+            RestaurantError::InThePast => write!(f, "that time has already passed"),
+            RestaurantError::NotOnSlot => write!(f, "reservations must start at a time slot"),
+        }
+    }
+}
+
+impl std::error::Error for RestaurantError {}
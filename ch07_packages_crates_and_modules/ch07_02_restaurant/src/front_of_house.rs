@@ -0,0 +1,3 @@
+pub mod hosting;
+pub mod reservations;
+pub mod serving;
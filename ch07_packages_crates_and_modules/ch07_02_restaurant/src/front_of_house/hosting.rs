@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+use crate::RestaurantError;
+
+pub type TableNumber = u32;
+
+/// A group of people that come to eat together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub name: String,
+    pub size: u32,
+}
+
+impl Party {
+    pub fn new(name: &str, size: u32) -> Party {
+        Party {
+            name: String::from(name),
+            size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    number: TableNumber,
+    seats: u32,
+    party: Option<Party>,
+}
+
+impl Table {
+    pub fn new(number: TableNumber, seats: u32) -> Table {
+        Table {
+            number,
+            seats,
+            party: None,
+        }
+    }
+
+    pub fn number(&self) -> TableNumber {
+        self.number
+    }
+
+    pub fn seats(&self) -> u32 {
+        self.seats
+    }
+
+    /// The party sitting at the table, if any.
+    pub fn party(&self) -> Option<&Party> {
+        self.party.as_ref()
+    }
+
+    pub fn is_free(&self) -> bool {
+        self.party.is_none()
+    }
+}
+
+/// Keeps the waitlist and decides where each party sits.
+#[derive(Debug, Clone)]
+pub struct Host {
+    tables: Vec<Table>,
+    waitlist: VecDeque<Party>,
+}
+
+impl Host {
+    pub fn new(tables: Vec<Table>) -> Host {
+        Host {
+            tables,
+            waitlist: VecDeque::new(),
+        }
+    }
+
+    /// Adds a party to the end of the waitlist and returns its position,
+    /// starting at 1.
+    pub fn add_to_waitlist(&mut self, party: Party) -> Result<usize, RestaurantError> {
+        if party.size == 0 {
+            return Err(RestaurantError::EmptyParty);
+        }
+        if self.tables.iter().all(|table| table.seats < party.size) {
+            return Err(RestaurantError::PartyTooLarge(party.size));
+        }
+
+        self.waitlist.push_back(party);
+        Ok(self.waitlist.len())
+    }
+
+    /// Parties waiting for a table, first to arrive first.
+    pub fn waitlist(&self) -> impl Iterator<Item = &Party> {
+        self.waitlist.iter()
+    }
+
+    // El Scheduler junta mesas, así que decide él quién entra en la lista y
+    // quién sale de ella
+    pub(crate) fn waitlist_mut(&mut self) -> &mut VecDeque<Party> {
+        &mut self.waitlist
+    }
+
+    /// Seats the first party in the waitlist that fits at a free table, at the
+    /// smallest such table. Returns the table and the party, or `None` if no
+    /// waiting party fits anywhere right now.
+    pub fn sit_at_table(&mut self) -> Option<(TableNumber, Party)> {
+        // Un grupo grande esperando una mesa grande no bloquea a los pequeños.
+        let (position, index) = self.waitlist.iter().enumerate().find_map(|(position, party)| {
+            self.smallest_free_table(party.size).map(|index| (position, index))
+        })?;
+
+        let party = self.waitlist.remove(position)?;
+        let table = &mut self.tables[index];
+        table.party = Some(party.clone());
+        Some((table.number, party))
+    }
+
+    /// Sits a party at a specific free table, without going through the
+    /// waitlist.
+    pub(crate) fn seat(
+        &mut self,
+        number: TableNumber,
+        party: Party,
+    ) -> Result<(), RestaurantError> {
+        let table = self
+            .tables
+            .iter_mut()
+            .find(|table| table.number == number)
+            .ok_or(RestaurantError::UnknownTable(number))?;
+
+        if !table.is_free() {
+            return Err(RestaurantError::TableOccupied(number));
+        }
+        table.party = Some(party);
+        Ok(())
+    }
+
+    /// Frees a table when its party leaves, returning the party.
+    pub fn free_table(&mut self, number: TableNumber) -> Result<Party, RestaurantError> {
+        let table = self
+            .tables
+            .iter_mut()
+            .find(|table| table.number == number)
+            .ok_or(RestaurantError::UnknownTable(number))?;
+
+        table
+            .party
+            .take()
+            .ok_or(RestaurantError::TableNotOccupied(number))
+    }
+
+    pub fn table(&self, number: TableNumber) -> Option<&Table> {
+        self.tables.iter().find(|table| table.number == number)
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    fn smallest_free_table(&self, size: u32) -> Option<usize> {
+        self.tables
+            .iter()
+            .enumerate()
+            .filter(|(_, table)| table.is_free() && table.seats >= size)
+            .min_by_key(|(_, table)| table.seats)
+            .map(|(index, _)| index)
+    }
+}
@@ -0,0 +1,508 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+
+use chrono::{Duration, Local, NaiveDateTime, Timelike};
+
+use crate::front_of_house::hosting::{Host, Party, Table, TableNumber};
+use crate::RestaurantError;
+
+pub type ReservationId = u32;
+
+/// Where the scheduler gets the current time from.
+pub trait Clock {
+    fn now(&self) -> NaiveDateTime;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> NaiveDateTime {
+        (**self).now()
+    }
+}
+
+/// The local time of the computer.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// A clock that only moves when told to, for simulations and tests.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Cell<NaiveDateTime>,
+}
+
+impl ManualClock {
+    pub fn new(start: NaiveDateTime) -> ManualClock {
+        ManualClock {
+            now: Cell::new(start),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    pub fn set(&self, now: NaiveDateTime) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> NaiveDateTime {
+        self.now.get()
+    }
+}
+
+/// Rules the scheduler follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Reservations start at a multiple of this many minutes past the hour.
+    pub slot: Duration,
+    /// How long a party keeps its tables.
+    pub dining: Duration,
+    /// How late a party can arrive before its tables are given to others.
+    pub no_show_after: Duration,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            slot: Duration::minutes(15),
+            dining: Duration::minutes(90),
+            no_show_after: Duration::minutes(15),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReservationStatus {
+    /// Waiting for the party to arrive.
+    Booked,
+    Seated,
+    /// The party left and the tables are free again.
+    Finished,
+    Cancelled,
+    /// The party didn't arrive in time.
+    NoShow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    id: ReservationId,
+    party: Party,
+    tables: Vec<TableNumber>,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    status: ReservationStatus,
+}
+
+impl Reservation {
+    pub fn id(&self) -> ReservationId {
+        self.id
+    }
+
+    pub fn party(&self) -> &Party {
+        &self.party
+    }
+
+    /// The tables held for the party, put together if there's more than one.
+    pub fn tables(&self) -> &[TableNumber] {
+        &self.tables
+    }
+
+    pub fn start(&self) -> NaiveDateTime {
+        self.start
+    }
+
+    pub fn end(&self) -> NaiveDateTime {
+        self.end
+    }
+
+    pub fn status(&self) -> ReservationStatus {
+        self.status
+    }
+
+    /* Una reserva ocupa sus mesas desde que empieza hasta que termina.
+     * Si el grupo ya está sentado, las ocupa hasta que se vaya aunque se
+     * pase de la hora prevista. Si se pasó el tiempo de espera y no ha
+     * llegado, ya no ocupa nada aunque nadie haya llamado a
+     * release_no_shows.
+     */
+    fn holds(
+        &self,
+        table: TableNumber,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        now: NaiveDateTime,
+        policy: &Policy,
+    ) -> bool {
+        let until = match self.status {
+            ReservationStatus::Booked if self.start + policy.no_show_after <= now => return false,
+            ReservationStatus::Booked => self.end,
+            ReservationStatus::Seated => self.end.max(now + Duration::minutes(1)),
+            _ => return false,
+        };
+        self.tables.contains(&table) && self.start < end && start < until
+    }
+}
+
+/// Books tables ahead of time and seats walk-ins in between reservations.
+///
+/// The tables and the waitlist are those of a [`Host`]: parties sit at its
+/// tables when they check in and leave them when they finish. The current
+/// time comes from a [`Clock`], so a [`ManualClock`] makes the scheduler
+/// completely predictable.
+///
+/// # Examples
+/// ```
+/// use ch07_02_restaurant::front_of_house::hosting::{Party, Table};
+/// use ch07_02_restaurant::front_of_house::reservations::{ManualClock, Scheduler};
+/// use chrono::{Duration, NaiveDate};
+///
+/// let opening = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap().and_hms_opt(19, 0, 0).unwrap();
+/// let clock = ManualClock::new(opening);
+/// let mut scheduler = Scheduler::new(vec![Table::new(1, 2), Table::new(2, 4)], &clock);
+///
+/// // Seis personas caben juntando las dos mesas
+/// let id = scheduler.reserve(Party::new("Ferris", 6), opening + Duration::hours(1)).unwrap();
+/// assert_eq!(&[1, 2], scheduler.reservation(id).unwrap().tables());
+///
+/// clock.advance(Duration::minutes(65));
+/// assert_eq!(Ok(vec![1, 2]), scheduler.check_in(id));
+/// assert_eq!("Ferris", scheduler.host().table(2).unwrap().party().unwrap().name);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler<C> {
+    host: Host,
+    reservations: Vec<Reservation>,
+    policy: Policy,
+    clock: C,
+    next_id: ReservationId,
+}
+
+impl<C: Clock> Scheduler<C> {
+    pub fn new(tables: Vec<Table>, clock: C) -> Scheduler<C> {
+        Scheduler::with_policy(tables, clock, Policy::default())
+    }
+
+    pub fn with_policy(tables: Vec<Table>, clock: C, policy: Policy) -> Scheduler<C> {
+        Scheduler {
+            host: Host::new(tables),
+            reservations: Vec::new(),
+            policy,
+            clock,
+            next_id: 1,
+        }
+    }
+
+    pub fn now(&self) -> NaiveDateTime {
+        self.clock.now()
+    }
+
+    /// Books tables for a party from `start` for as long as the policy says.
+    /// A single table is preferred; if none is big enough or free, several
+    /// are put together.
+    pub fn reserve(
+        &mut self,
+        party: Party,
+        start: NaiveDateTime,
+    ) -> Result<ReservationId, RestaurantError> {
+        self.check_party(&party)?;
+        if start < self.now() {
+            return Err(RestaurantError::InThePast);
+        }
+        if !self.on_slot(start) {
+            return Err(RestaurantError::NotOnSlot);
+        }
+
+        let end = start + self.policy.dining;
+        let tables = self
+            .find_tables(party.size, start, end)
+            .ok_or(RestaurantError::NoTableAvailable)?;
+        Ok(self.add(party, tables, start, end, ReservationStatus::Booked))
+    }
+
+    /// Cancels a reservation that hasn't been seated yet.
+    pub fn cancel(&mut self, id: ReservationId) -> Result<(), RestaurantError> {
+        self.update(id, ReservationStatus::Booked, ReservationStatus::Cancelled)?;
+        Ok(())
+    }
+
+    /// Seats a party that arrived for its reservation and returns its tables.
+    /// Arriving after the no-show timeout is too late, and arriving early,
+    /// or while someone is still sitting at the tables, only works once they
+    /// are free.
+    pub fn check_in(&mut self, id: ReservationId) -> Result<Vec<TableNumber>, RestaurantError> {
+        self.release_no_shows();
+        let now = self.now();
+
+        if let Some(early) = self.reservation(id).filter(|r| r.start > now) {
+            if !early
+                .tables
+                .iter()
+                .all(|&table| self.is_free(table, now, early.start))
+            {
+                return Err(RestaurantError::NoTableAvailable);
+            }
+        }
+
+        // El grupo anterior puede seguir sentado aunque se le haya acabado el tiempo
+        if let Some(reservation) = self.reservation(id) {
+            let taken = reservation.tables.iter().any(|&table| {
+                self.host
+                    .table(table)
+                    .is_some_and(|table| !table.is_free())
+            });
+            if taken && reservation.status == ReservationStatus::Booked {
+                return Err(RestaurantError::NoTableAvailable);
+            }
+        }
+
+        let reservation = self.update(id, ReservationStatus::Booked, ReservationStatus::Seated)?;
+        reservation.start = reservation.start.min(now);
+        let (party, tables) = (reservation.party.clone(), reservation.tables.clone());
+        self.seat(&party, &tables);
+        Ok(tables)
+    }
+
+    /// The party leaves and its tables are free from now on.
+    pub fn finish(&mut self, id: ReservationId) -> Result<(), RestaurantError> {
+        let now = self.now();
+        let reservation =
+            self.update(id, ReservationStatus::Seated, ReservationStatus::Finished)?;
+        reservation.end = now;
+        for table in reservation.tables.clone() {
+            self.host
+                .free_table(table)
+                .expect("seated reservations always have their tables taken");
+        }
+        Ok(())
+    }
+
+    /// Marks as no-shows the reservations whose party is later than the
+    /// policy allows, freeing their tables. Returns the ones released.
+    pub fn release_no_shows(&mut self) -> Vec<ReservationId> {
+        let deadline = self.now() - self.policy.no_show_after;
+        self.reservations
+            .iter_mut()
+            .filter(|r| r.status == ReservationStatus::Booked && r.start <= deadline)
+            .map(|r| {
+                r.status = ReservationStatus::NoShow;
+                r.id
+            })
+            .collect()
+    }
+
+    /// Adds a party without a reservation to the end of the waitlist and
+    /// returns its position, starting at 1.
+    pub fn add_to_waitlist(&mut self, party: Party) -> Result<usize, RestaurantError> {
+        self.check_party(&party)?;
+        let waitlist = self.host.waitlist_mut();
+        waitlist.push_back(party);
+        Ok(waitlist.len())
+    }
+
+    pub fn waitlist(&self) -> impl Iterator<Item = &Party> {
+        self.host.waitlist()
+    }
+
+    /// Seats as many waiting parties as possible, in order of arrival, at
+    /// tables that are free now and stay free until they finish eating.
+    /// Returns the reservations created for them.
+    pub fn seat_walk_ins(&mut self) -> Vec<ReservationId> {
+        self.release_no_shows();
+        let start = self.now();
+        let end = start + self.policy.dining;
+
+        let mut seated = Vec::new();
+        let mut waiting = VecDeque::new();
+        while let Some(party) = self.host.waitlist_mut().pop_front() {
+            match self.find_tables(party.size, start, end) {
+                Some(tables) => {
+                    self.seat(&party, &tables);
+                    seated.push(self.add(party, tables, start, end, ReservationStatus::Seated))
+                }
+                None => waiting.push_back(party),
+            }
+        }
+        *self.host.waitlist_mut() = waiting;
+        seated
+    }
+
+    pub fn reservation(&self, id: ReservationId) -> Option<&Reservation> {
+        self.reservations.iter().find(|r| r.id == id)
+    }
+
+    pub fn reservations(&self) -> &[Reservation] {
+        &self.reservations
+    }
+
+    /// The host, with the tables and who is sitting at them.
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    /// Tables with nobody sitting at them and no reservation starting now.
+    pub fn free_tables(&self) -> Vec<TableNumber> {
+        let now = self.now();
+        self.host
+            .tables()
+            .iter()
+            .map(Table::number)
+            .filter(|&table| self.is_free(table, now, now + Duration::minutes(1)))
+            .collect()
+    }
+
+    fn check_party(&self, party: &Party) -> Result<(), RestaurantError> {
+        if party.size == 0 {
+            return Err(RestaurantError::EmptyParty);
+        }
+        if self.host.tables().iter().map(Table::seats).sum::<u32>() < party.size {
+            return Err(RestaurantError::PartyTooLarge(party.size));
+        }
+        Ok(())
+    }
+
+    fn on_slot(&self, start: NaiveDateTime) -> bool {
+        let slot = self.policy.slot.num_seconds().max(1);
+        let seconds = start.num_seconds_from_midnight() as i64;
+        seconds % slot == 0 && start.nanosecond() == 0
+    }
+
+    fn is_free(&self, table: TableNumber, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        let now = self.now();
+        !self
+            .reservations
+            .iter()
+            .any(|r| r.holds(table, start, end, now, &self.policy))
+    }
+
+    /* Busca las mesas libres que sienten al grupo usando el menor número de
+     * mesas y, entre esas, el menor número de sillas: primero se prueba con
+     * una mesa, luego con dos, etc.
+     */
+    fn find_tables(
+        &self,
+        size: u32,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+    ) -> Option<Vec<TableNumber>> {
+        let mut free: Vec<&Table> = self
+            .host
+            .tables()
+            .iter()
+            .filter(|table| self.is_free(table.number(), start, end))
+            .collect();
+        free.sort_by_key(|table| (table.seats(), table.number()));
+
+        for count in 1..=free.len() {
+            let mut best: Option<(u32, Vec<usize>)> = None;
+            let mut chosen = Vec::with_capacity(count);
+            smallest_combination(&free, size, count, 0, 0, &mut chosen, &mut best);
+
+            if let Some((_, indices)) = best {
+                let mut tables: Vec<TableNumber> =
+                    indices.into_iter().map(|i| free[i].number()).collect();
+                tables.sort_unstable();
+                return Some(tables);
+            }
+        }
+        None
+    }
+
+    // Las mesas de una reserva están libres cuando empieza, así que el Host
+    // siempre tiene sitio
+    fn seat(&mut self, party: &Party, tables: &[TableNumber]) {
+        for &table in tables {
+            self.host
+                .seat(table, party.clone())
+                .expect("the tables of a reservation are free when it starts");
+        }
+    }
+
+    fn add(
+        &mut self,
+        party: Party,
+        tables: Vec<TableNumber>,
+        start: NaiveDateTime,
+        end: NaiveDateTime,
+        status: ReservationStatus,
+    ) -> ReservationId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.reservations.push(Reservation {
+            id,
+            party,
+            tables,
+            start,
+            end,
+            status,
+        });
+        id
+    }
+
+    fn update(
+        &mut self,
+        id: ReservationId,
+        from: ReservationStatus,
+        to: ReservationStatus,
+    ) -> Result<&mut Reservation, RestaurantError> {
+        let reservation = self
+            .reservations
+            .iter_mut()
+            .find(|r| r.id == id)
+            .ok_or(RestaurantError::UnknownReservation(id))?;
+
+        if reservation.status != from {
+            return Err(RestaurantError::WrongReservationStatus(id));
+        }
+        reservation.status = to;
+        Ok(reservation)
+    }
+}
+
+/* Combinaciones de `count` mesas (en orden) con al menos `size` sillas.
+ * Las mesas vienen ordenadas de menos a más sillas, así que se puede podar:
+ * se salta una mesa si ni con las más grandes se llega a `size`, y se deja de
+ * buscar en cuanto ni con las siguientes más pequeñas se mejora lo encontrado.
+ */
+fn smallest_combination(
+    tables: &[&Table],
+    size: u32,
+    count: usize,
+    from: usize,
+    seats: u32,
+    chosen: &mut Vec<usize>,
+    best: &mut Option<(u32, Vec<usize>)>,
+) {
+    let left = count - chosen.len();
+    if left == 0 {
+        if seats >= size && best.as_ref().is_none_or(|(fewest, _)| seats < *fewest) {
+            *best = Some((seats, chosen.clone()));
+        }
+        return;
+    }
+
+    let sum = |range: std::ops::Range<usize>| -> u32 {
+        tables[range].iter().map(|table| table.seats()).sum()
+    };
+    for index in from..=tables.len().saturating_sub(left) {
+        let fewest = seats + sum(index..index + left);
+        if best.as_ref().is_some_and(|(best, _)| fewest >= *best) {
+            break;
+        }
+        let most = seats + tables[index].seats() + sum(tables.len() - (left - 1)..tables.len());
+        if most < size {
+            continue;
+        }
+
+        chosen.push(index);
+        let seats = seats + tables[index].seats();
+        smallest_combination(tables, size, count, index + 1, seats, chosen, best);
+        chosen.pop();
+    }
+}
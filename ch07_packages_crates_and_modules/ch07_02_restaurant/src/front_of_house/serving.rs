@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use crate::front_of_house::hosting::TableNumber;
+use crate::RestaurantError;
+
+pub type OrderId = u32;
+
+/// A dish or drink in an order. The price is in cents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderItem {
+    pub name: String,
+    pub price: u64,
+}
+
+impl OrderItem {
+    pub fn new(name: &str, price: u64) -> OrderItem {
+        OrderItem {
+            name: String::from(name),
+            price,
+        }
+    }
+}
+
+/// Where an order is: `Placed` (in the kitchen queue), `Ready` (cooked),
+/// `Served` (at the table) and finally `Paid`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderStatus {
+    Placed,
+    Ready,
+    Served,
+    Paid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    id: OrderId,
+    table: TableNumber,
+    items: Vec<OrderItem>,
+    status: OrderStatus,
+}
+
+impl Order {
+    pub fn id(&self) -> OrderId {
+        self.id
+    }
+
+    pub fn table(&self) -> TableNumber {
+        self.table
+    }
+
+    pub fn items(&self) -> &[OrderItem] {
+        &self.items
+    }
+
+    pub fn status(&self) -> OrderStatus {
+        self.status
+    }
+}
+
+/// What a table has to pay, in cents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bill {
+    pub table: TableNumber,
+    pub orders: Vec<OrderId>,
+    pub items: Vec<OrderItem>,
+}
+
+impl Bill {
+    pub fn total(&self) -> u64 {
+        self.items.iter().map(|item| item.price).sum()
+    }
+}
+
+/// Takes orders, serves them and collects payments.
+#[derive(Debug, Clone, Default)]
+pub struct Waiter {
+    orders: BTreeMap<OrderId, Order>,
+    next_id: OrderId,
+}
+
+impl Waiter {
+    pub fn new() -> Waiter {
+        Waiter::default()
+    }
+
+    /// Writes down a new order for a table and returns its id.
+    pub fn take_order(
+        &mut self,
+        table: TableNumber,
+        items: Vec<OrderItem>,
+    ) -> Result<OrderId, RestaurantError> {
+        if items.is_empty() {
+            return Err(RestaurantError::EmptyOrder);
+        }
+
+        self.next_id += 1;
+        let order = Order {
+            id: self.next_id,
+            table,
+            items,
+            status: OrderStatus::Placed,
+        };
+        self.orders.insert(order.id, order);
+        Ok(self.next_id)
+    }
+
+    /// Brings a cooked order to its table.
+    pub fn serve_order(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+        self.advance(id, OrderStatus::Ready, OrderStatus::Served)
+    }
+
+    /// Charges a table for every order served to it since it last paid.
+    pub fn take_payment(&mut self, table: TableNumber) -> Result<Bill, RestaurantError> {
+        let unpaid: Vec<&mut Order> = self
+            .orders
+            .values_mut()
+            .filter(|order| order.table == table && order.status != OrderStatus::Paid)
+            .collect();
+
+        if unpaid.is_empty() {
+            return Err(RestaurantError::NothingToPay(table));
+        }
+        if unpaid.iter().any(|order| order.status != OrderStatus::Served) {
+            return Err(RestaurantError::OrdersPending(table));
+        }
+
+        let mut bill = Bill {
+            table,
+            orders: Vec::new(),
+            items: Vec::new(),
+        };
+        for order in unpaid {
+            order.status = OrderStatus::Paid;
+            bill.orders.push(order.id);
+            bill.items.extend(order.items.iter().cloned());
+        }
+        Ok(bill)
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&Order> {
+        self.orders.get(&id)
+    }
+
+    /// Every order of a table, oldest first.
+    pub fn orders_for(&self, table: TableNumber) -> impl Iterator<Item = &Order> {
+        self.orders.values().filter(move |order| order.table == table)
+    }
+
+    // La cocina usa estas dos para avisar de que un pedido está listo o
+    // de que hay que repetirlo.
+    pub(crate) fn mark_ready(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+        self.advance(id, OrderStatus::Placed, OrderStatus::Ready)
+    }
+
+    pub(crate) fn send_back(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+        self.advance(id, OrderStatus::Served, OrderStatus::Placed)
+    }
+
+    fn advance(
+        &mut self,
+        id: OrderId,
+        from: OrderStatus,
+        to: OrderStatus,
+    ) -> Result<(), RestaurantError> {
+        let order = self
+            .orders
+            .get_mut(&id)
+            .ok_or(RestaurantError::UnknownOrder(id))?;
+
+        if order.status != from {
+            return Err(RestaurantError::WrongOrderStatus(id));
+        }
+        order.status = to;
+        Ok(())
+    }
+}
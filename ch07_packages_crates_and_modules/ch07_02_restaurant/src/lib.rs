@@ -25,81 +25,232 @@ mod tests {
 /* Module: definido con mod [nombre].
  * Los módulos padre no pueden ver el contenido de los hijos, pero estos sí 
  * pueden ver los de su padre. Por defecto, los hijos son privados.
+ *
+ * Los módulos están separados en archivos (ver ch07_04_module_separation):
+ * front_of_house.rs declara hosting y serving, que están en front_of_house/.
  */ 
-mod front_of_house {
-    pub mod hosting {
-        pub fn add_to_waitlist() {}
+pub mod back_of_house;
+pub mod billing;
+pub mod front_of_house;
+pub mod menu;
+mod error;
 
-        // private
-        fn sit_at_table() {}
-    }
-    
-    // private
-    mod serving {
-        fn take_order() {}
-
-        fn serve_order() {}
+pub use error::RestaurantError;
 
-        fn take_payment() {}
-    }
-}
+use back_of_house::Kitchen;
+use front_of_house::hosting::{Host, Party, Table, TableNumber};
+use front_of_house::serving::{Bill, Order, OrderId, OrderItem, Waiter};
 
 
 // Paths
-pub fn eat_at_restaurant() {
+pub fn eat_at_restaurant() -> Result<(), RestaurantError> {
     // Absolute path
-    crate::front_of_house::hosting::add_to_waitlist();
+    let mut host = crate::front_of_house::hosting::Host::new(vec![Table::new(1, 4)]);
     
     // Relative path
-    front_of_house::hosting::add_to_waitlist();
+    host.add_to_waitlist(front_of_house::hosting::Party::new("Ferris", 2))?;
+    host.sit_at_table();
+    Ok(())
 }
 
 /* Estructura para el compilador:
 
 crate
- └── front_of_house
-     ├── hosting
-     │   ├── add_to_waitlist
-     │   └── seat_at_table
-     └── serving
-         ├── take_order
-         ├── serve_order
-         └── take_payment
+ ├── back_of_house
+ │   ├── Kitchen
+ │   │   ├── cook_order
+ │   │   └── fix_incorrect_order
+ │   └── simulation
+ │       └── KitchenSimulation
+ ├── billing
+ │   └── Receipt
+ │       ├── split_evenly
+ │       └── split_by_item
+ ├── error
+ │   └── RestaurantError
+ ├── front_of_house
+ │   ├── hosting
+ │   │   └── Host
+ │   │       ├── add_to_waitlist
+ │   │       └── sit_at_table
+ │   ├── reservations
+ │   │   └── Scheduler
+ │   └── serving
+ │       └── Waiter
+ │           ├── take_order
+ │           ├── serve_order
+ │           └── take_payment
+ ├── house
+ │   ├── Breakfast
+ │   └── Appetizer
+ └── menu
+     └── Menu
 
 crate es un módulo que se crea por defecto para el crate root (lib.rs).
 Dentro está el módulo front_of_house, dentro del cual están hosting y serving
-con sus funciones.
+con sus tipos y funciones.
 */
 
 
-// super
+/* super: referencia al módulo padre. Antes de separar los módulos en archivos
+ * el ejemplo era este; ahora back_of_house.rs usa super para llegar a OrderId.
+
 fn serve_order() {}
 
 mod back_of_house {
     fn fix_incorrect_order() {
         cook_order();
-        // super: referencia al módulo padre (crate)
         super::serve_order();
     }
 
     fn cook_order() {}
 }
+ */
+
+/// The whole restaurant: the host seats parties, the waiter takes their
+/// orders to the kitchen, serves them once cooked and charges the table.
+///
+/// # Examples
+/// ```
+/// use ch07_02_restaurant::Restaurant;
+/// use ch07_02_restaurant::front_of_house::hosting::{Party, Table};
+/// use ch07_02_restaurant::front_of_house::serving::OrderItem;
+///
+/// let mut restaurant = Restaurant::new(vec![Table::new(1, 2), Table::new(2, 4)]);
+///
+/// restaurant.add_to_waitlist(Party::new("Ferris", 3)).unwrap();
+/// let (table, _) = restaurant.sit_at_table().unwrap();
+/// assert_eq!(2, table);
+///
+/// let order = restaurant.take_order(table, vec![OrderItem::new("Soup", 450)]).unwrap();
+/// restaurant.cook_order();
+/// restaurant.serve_order(order).unwrap();
+///
+/// let bill = restaurant.take_payment(table).unwrap();
+/// assert_eq!(450, bill.total());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Restaurant {
+    host: Host,
+    waiter: Waiter,
+    kitchen: Kitchen,
+}
+
+impl Restaurant {
+    pub fn new(tables: Vec<Table>) -> Restaurant {
+        Restaurant {
+            host: Host::new(tables),
+            waiter: Waiter::new(),
+            kitchen: Kitchen::new(),
+        }
+    }
+
+    /// Adds a party to the waitlist and returns its position in it.
+    pub fn add_to_waitlist(&mut self, party: Party) -> Result<usize, RestaurantError> {
+        self.host.add_to_waitlist(party)
+    }
+
+    /// Seats the next party from the waitlist that fits at a free table.
+    pub fn sit_at_table(&mut self) -> Option<(TableNumber, Party)> {
+        self.host.sit_at_table()
+    }
+
+    /// Takes an order from an occupied table and sends it to the kitchen.
+    pub fn take_order(
+        &mut self,
+        table: TableNumber,
+        items: Vec<OrderItem>,
+    ) -> Result<OrderId, RestaurantError> {
+        self.occupied_table(table)?;
+
+        let id = self.waiter.take_order(table, items)?;
+        self.kitchen.receive_order(id);
+        Ok(id)
+    }
+
+    /// Cooks the next order in the kitchen queue, leaving it ready to serve.
+    pub fn cook_order(&mut self) -> Option<OrderId> {
+        let id = self.kitchen.cook_order()?;
+        self.waiter
+            .mark_ready(id)
+            .expect("orders in the kitchen queue are always placed");
+        Some(id)
+    }
+
+    /// Serves a cooked order to its table.
+    pub fn serve_order(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+        self.waiter.serve_order(id)
+    }
+
+    /// Sends a served order back to the kitchen, where it's cooked again
+    /// before any other order.
+    pub fn fix_incorrect_order(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+        self.waiter.send_back(id)?;
+        self.kitchen.fix_incorrect_order(id);
+        Ok(())
+    }
+
+    /// Charges a table for its served orders and frees it.
+    pub fn take_payment(&mut self, table: TableNumber) -> Result<Bill, RestaurantError> {
+        self.occupied_table(table)?;
+
+        let bill = self.waiter.take_payment(table)?;
+        self.host.free_table(table)?;
+        Ok(bill)
+    }
+
+    pub fn host(&self) -> &Host {
+        &self.host
+    }
+
+    pub fn kitchen(&self) -> &Kitchen {
+        &self.kitchen
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&Order> {
+        self.waiter.order(id)
+    }
+
+    fn occupied_table(&self, table: TableNumber) -> Result<&Party, RestaurantError> {
+        self.host
+            .table(table)
+            .ok_or(RestaurantError::UnknownTable(table))?
+            .party()
+            .ok_or(RestaurantError::TableNotOccupied(table))
+    }
+}
 
 
 // privacy rules in items
-mod house {
+pub mod house {
+    use crate::menu::Season;
+
     pub struct Breakfast {
         pub toast: String,
         seasonal_fruit: String,
     }
 
     impl Breakfast {
-        pub fn summer(toast: &str) -> Breakfast {
+        /// Breakfast with the fruit of the current season.
+        pub fn new(toast: &str) -> Breakfast {
+            Breakfast::in_season(toast, Season::current())
+        }
+
+        pub fn in_season(toast: &str, season: Season) -> Breakfast {
             Breakfast {
                 toast: String::from(toast),
-                seasonal_fruit: String::from("peaches"),
+                seasonal_fruit: String::from(season.fruit()),
             }
         }
+
+        pub fn summer(toast: &str) -> Breakfast {
+            Breakfast::in_season(toast, Season::Summer)
+        }
+
+        // El campo privado solo se puede leer desde fuera a través de un método
+        pub fn seasonal_fruit(&self) -> &str {
+            &self.seasonal_fruit
+        }
     }
 
     pub enum Appetizer {
@@ -109,12 +260,14 @@ mod house {
 }
 
 pub fn eat_breakfast() {
-    /* Aquí dentro solo se puede declarar breakfast mediante la función summer, ya que
+    /* Aquí dentro solo se puede declarar breakfast mediante funciones como summer, ya que
      * Breakfast tiene un campo privado (seasonal_fruit)
      */
     let mut meal = house::Breakfast::summer("Rye");
+    // El campo público sí se puede cambiar
+    meal.toast = String::from("Wheat");
 
     // Las propiedades de los enums se vuelven públicas si el enum es público
-    let order1 = house::Appetizer::Soup;
-    let order2 = house::Appetizer::Salad;
-}
\ No newline at end of file
+    let _order1 = house::Appetizer::Soup;
+    let _order2 = house::Appetizer::Salad;
+}
@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde::Deserialize;
+
+use crate::front_of_house::serving::OrderItem;
+
+/// Seasons of the year, by month in the northern hemisphere: spring from
+/// March to May, summer from June to August, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn of(date: NaiveDate) -> Season {
+        match date.month() {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    /// The season of today's local date.
+    pub fn current() -> Season {
+        Season::of(Local::now().date_naive())
+    }
+
+    /// The fruit served with breakfast in this season.
+    pub fn fruit(self) -> &'static str {
+        match self {
+            Season::Spring => "strawberries",
+            Season::Summer => "peaches",
+            Season::Autumn => "apples",
+            Season::Winter => "oranges",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Breakfast,
+    Appetizer,
+    Main,
+    Dessert,
+    Drink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DietaryTag {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    DairyFree,
+    NutFree,
+}
+
+/// A dish or drink on the menu. The price is in cents.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MenuItem {
+    pub name: String,
+    pub price: u64,
+    pub category: Category,
+    /// Seasons when the item is available. Empty means all year round.
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    #[serde(default)]
+    pub dietary: Vec<DietaryTag>,
+}
+
+impl MenuItem {
+    pub fn is_available_on(&self, date: NaiveDate) -> bool {
+        self.seasons.is_empty() || self.seasons.contains(&Season::of(date))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuError {
+    /// The file couldn't be read.
+    Io(String),
+    /// The file isn't valid TOML or JSON, or doesn't describe a menu.
+    Parse(String),
+    /// The file extension isn't `.toml` or `.json`.
+    UnknownFormat(String),
+    DuplicateItem(String),
+    UnknownItem(String),
+    /// The item exists, but not in the season of the date asked for.
+    OutOfSeason(String),
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuError::Io(e) => write!(f, "couldn't read the menu: {}", e),
+            MenuError::Parse(e) => write!(f, "invalid menu: {}", e),
+            MenuError::UnknownFormat(path) => {
+                write!(f, "{} isn't a .toml or .json file", path)
+            }
+            MenuError::DuplicateItem(name) => write!(f, "{} is on the menu twice", name),
+            MenuError::UnknownItem(name) => write!(f, "{} isn't on the menu", name),
+            MenuError::OutOfSeason(name) => write!(f, "{} is out of season", name),
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+// Forma del archivo: una lista de [[items]] en TOML o {"items": [...]} en JSON.
+#[derive(Deserialize)]
+struct MenuFile {
+    items: Vec<MenuItem>,
+}
+
+/// Every item the restaurant can serve.
+///
+/// # Examples
+/// ```
+/// use ch07_02_restaurant::menu::{Category, Menu};
+/// use chrono::NaiveDate;
+///
+/// let menu = Menu::from_toml(r#"
+///     [[items]]
+///     name = "Gazpacho"
+///     price = 550
+///     category = "appetizer"
+///     seasons = ["summer"]
+///     dietary = ["vegan"]
+/// "#).unwrap();
+///
+/// let july = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+/// let item = menu.order_item("Gazpacho", july).unwrap();
+/// assert_eq!(550, item.price);
+///
+/// let january = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+/// assert!(menu.order_item("Gazpacho", january).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// Creates a menu, checking that no two items share a name.
+    pub fn new(items: Vec<MenuItem>) -> Result<Menu, MenuError> {
+        let mut names = HashSet::new();
+        for item in &items {
+            if !names.insert(item.name.as_str()) {
+                return Err(MenuError::DuplicateItem(item.name.clone()));
+            }
+        }
+
+        Ok(Menu { items })
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Menu, MenuError> {
+        let file: MenuFile = toml::from_str(toml).map_err(|e| MenuError::Parse(e.to_string()))?;
+        Menu::new(file.items)
+    }
+
+    pub fn from_json(json: &str) -> Result<Menu, MenuError> {
+        let file: MenuFile =
+            serde_json::from_str(json).map_err(|e| MenuError::Parse(e.to_string()))?;
+        Menu::new(file.items)
+    }
+
+    /// Loads a menu from a `.toml` or `.json` file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Menu, MenuError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| MenuError::Io(e.to_string()))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Menu::from_toml(&contents),
+            Some("json") => Menu::from_json(&contents),
+            _ => Err(MenuError::UnknownFormat(path.display().to_string())),
+        }
+    }
+
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MenuItem> {
+        self.items.iter().find(|item| item.name == name)
+    }
+
+    /// Items that can be ordered on the given date.
+    pub fn available_on(&self, date: NaiveDate) -> impl Iterator<Item = &MenuItem> {
+        self.items.iter().filter(move |item| item.is_available_on(date))
+    }
+
+    pub fn in_category(&self, category: Category) -> impl Iterator<Item = &MenuItem> {
+        self.items.iter().filter(move |item| item.category == category)
+    }
+
+    pub fn with_tag(&self, tag: DietaryTag) -> impl Iterator<Item = &MenuItem> {
+        self.items.iter().filter(move |item| item.dietary.contains(&tag))
+    }
+
+    /// Looks up an item to put in an order, if it's available on `date`.
+    pub fn order_item(&self, name: &str, date: NaiveDate) -> Result<OrderItem, MenuError> {
+        let item = self
+            .get(name)
+            .ok_or_else(|| MenuError::UnknownItem(String::from(name)))?;
+
+        if !item.is_available_on(date) {
+            return Err(MenuError::OutOfSeason(String::from(name)));
+        }
+        Ok(OrderItem::new(&item.name, item.price))
+    }
+}
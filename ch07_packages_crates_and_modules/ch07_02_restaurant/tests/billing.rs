@@ -0,0 +1,170 @@
+use ch07_02_restaurant::billing::{
+    format_cents, BillingError, Charges, Discount, Rate, Receipt, Tip,
+};
+use ch07_02_restaurant::front_of_house::hosting::{Party, Table};
+use ch07_02_restaurant::front_of_house::serving::{Bill, OrderItem};
+use ch07_02_restaurant::Restaurant;
+
+fn bill(prices: &[u64]) -> Bill {
+    Bill {
+        table: 3,
+        orders: vec![1],
+        items: prices
+            .iter()
+            .enumerate()
+            .map(|(i, &price)| OrderItem::new(&format!("Dish {}", i + 1), price))
+            .collect(),
+    }
+}
+
+#[test]
+fn rates_round_to_the_nearest_cent() {
+    assert_eq!(100, Rate::percent(10).of(1000));
+    assert_eq!(1, Rate::percent(10).of(5));
+    assert_eq!(0, Rate::percent(10).of(4));
+    assert_eq!(83, Rate::basis_points(825).of(1000));
+    assert_eq!(0, Rate::ZERO.of(1000));
+
+    assert_eq!("21%", Rate::percent(21).to_string());
+    assert_eq!(Rate::basis_points(u32::MAX), Rate::percent(u32::MAX));
+    assert_eq!("8.25%", Rate::basis_points(825).to_string());
+    assert_eq!("7.5%", Rate::basis_points(750).to_string());
+}
+
+#[test]
+fn no_charges() {
+    let receipt = Receipt::new(&bill(&[450, 600]), &Charges::default());
+    assert_eq!(1050, receipt.subtotal);
+    assert_eq!(1050, receipt.total);
+}
+
+#[test]
+fn percent_discount_is_taken_before_tax() {
+    let charges = Charges {
+        tax: Rate::percent(21),
+        discount: Some(Discount::Percent(Rate::percent(20))),
+        tip: Tip::Fixed(200),
+    };
+    let receipt = Receipt::new(&bill(&[1000, 1500]), &charges);
+
+    assert_eq!(2500, receipt.subtotal);
+    assert_eq!(500, receipt.discount);
+    assert_eq!(420, receipt.tax);
+    assert_eq!(200, receipt.tip);
+    assert_eq!(2620, receipt.total);
+}
+
+#[test]
+fn discount_never_goes_below_zero() {
+    let charges = Charges {
+        tax: Rate::percent(10),
+        discount: Some(Discount::Fixed(5000)),
+        tip: Tip::None,
+    };
+    let receipt = Receipt::new(&bill(&[1000]), &charges);
+
+    assert_eq!(1000, receipt.discount);
+    assert_eq!(0, receipt.tax);
+    assert_eq!(0, receipt.total);
+}
+
+#[test]
+fn even_split_adds_up_exactly() {
+    let receipt = Receipt::new(&bill(&[1000]), &Charges::default());
+    assert_eq!(vec![334, 333, 333], receipt.split_evenly(3).unwrap());
+
+    for diners in 1..=12 {
+        let parts = receipt.split_evenly(diners).unwrap();
+        assert_eq!(receipt.total, parts.iter().sum::<u64>());
+    }
+    assert_eq!(Err(BillingError::NoDiners), receipt.split_evenly(0));
+}
+
+#[test]
+fn split_by_item() {
+    let charges = Charges {
+        tax: Rate::percent(10),
+        discount: None,
+        tip: Tip::None,
+    };
+    // Diner 0 had the first dish, diner 1 the second and they shared the third.
+    let receipt = Receipt::new(&bill(&[1000, 2000, 1000]), &charges);
+    let parts = receipt
+        .split_by_item(2, &[vec![0], vec![1], vec![0, 1]])
+        .unwrap();
+
+    assert_eq!(4400, receipt.total);
+    assert_eq!(vec![1650, 2750], parts);
+}
+
+#[test]
+fn split_by_item_with_odd_cents() {
+    let charges = Charges {
+        tax: Rate::basis_points(825),
+        discount: Some(Discount::Fixed(99)),
+        tip: Tip::Percent(Rate::percent(18)),
+    };
+    let receipt = Receipt::new(&bill(&[333, 777, 1001]), &charges);
+    let parts = receipt
+        .split_by_item(3, &[vec![0, 1, 2], vec![1], vec![2, 0]])
+        .unwrap();
+
+    assert_eq!(receipt.total, parts.iter().sum::<u64>());
+}
+
+#[test]
+fn invalid_splits() {
+    let receipt = Receipt::new(&bill(&[1000, 2000]), &Charges::default());
+
+    assert_eq!(
+        Err(BillingError::WrongItemCount { expected: 2, found: 1 }),
+        receipt.split_by_item(2, &[vec![0]])
+    );
+    assert_eq!(
+        Err(BillingError::UnassignedItem(1)),
+        receipt.split_by_item(2, &[vec![0], vec![]])
+    );
+    assert_eq!(
+        Err(BillingError::UnknownDiner(2)),
+        receipt.split_by_item(2, &[vec![0], vec![2]])
+    );
+    assert_eq!(Err(BillingError::NoDiners), receipt.split_by_item(0, &[vec![], vec![]]));
+}
+
+#[test]
+fn render_receipt() {
+    let mut restaurant = Restaurant::new(vec![Table::new(3, 2)]);
+    restaurant.add_to_waitlist(Party::new("Ferris", 2)).unwrap();
+    restaurant.sit_at_table().unwrap();
+    let order = restaurant
+        .take_order(3, vec![OrderItem::new("Soup", 450), OrderItem::new("Salmon", 1650)])
+        .unwrap();
+    restaurant.cook_order();
+    restaurant.serve_order(order).unwrap();
+    let bill = restaurant.take_payment(3).unwrap();
+
+    let charges = Charges {
+        tax: Rate::percent(10),
+        discount: Some(Discount::Percent(Rate::percent(10))),
+        tip: Tip::Fixed(300),
+    };
+    let expected = "\
+Table 3
+Soup                        4.50
+Salmon                     16.50
+--------------------------------
+Subtotal                   21.00
+Discount                   -2.10
+Tax (10%)                   1.89
+Tip                         3.00
+--------------------------------
+Total                      23.79
+";
+    assert_eq!(expected, Receipt::new(&bill, &charges).render());
+}
+
+#[test]
+fn format_amounts() {
+    assert_eq!("0.05", format_cents(5));
+    assert_eq!("12.30", format_cents(1230));
+}
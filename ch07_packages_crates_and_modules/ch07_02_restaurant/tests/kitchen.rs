@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use ch07_02_restaurant::back_of_house::simulation::{Dish, KitchenSimulation, Pace, Ticket};
+use ch07_02_restaurant::RestaurantError;
+
+fn minutes(m: u64) -> Duration {
+    Duration::from_secs(m * 60)
+}
+
+fn ticket(order: u32, placed_at: u64, dishes: &[(&str, u64)]) -> Ticket {
+    Ticket {
+        order,
+        table: order * 10,
+        placed_at: minutes(placed_at),
+        dishes: dishes
+            .iter()
+            .map(|&(name, prep)| Dish::new(name, minutes(prep)))
+            .collect(),
+    }
+}
+
+fn dinner_service() -> Vec<Ticket> {
+    vec![
+        ticket(1, 0, &[("Soup", 5), ("Salmon", 15), ("Salad", 4)]),
+        ticket(2, 1, &[("Risotto", 20)]),
+        ticket(3, 3, &[("Flan", 3), ("Tart", 6)]),
+        ticket(4, 10, &[("Soup", 5)]),
+    ]
+}
+
+#[test]
+fn one_cook_cooks_everything_in_order() {
+    let report = KitchenSimulation::new(1).run(dinner_service()).unwrap();
+
+    let ready: Vec<(u32, Duration)> = report
+        .orders
+        .iter()
+        .map(|o| (o.order, o.ready_at))
+        .collect();
+    assert_eq!(
+        vec![
+            (1, minutes(24)),
+            (2, minutes(44)),
+            (3, minutes(53)),
+            (4, minutes(58))
+        ],
+        ready
+    );
+    assert!(report
+        .orders
+        .iter()
+        .flat_map(|o| &o.plates)
+        .all(|p| p.cook == 0));
+}
+
+#[test]
+fn expediter_reassembles_orders() {
+    let report = KitchenSimulation::new(3).run(dinner_service()).unwrap();
+
+    let first = report.orders.iter().find(|o| o.order == 1).unwrap();
+    assert_eq!(10, first.table);
+    let dishes: Vec<&str> = first.plates.iter().map(|p| p.dish.as_str()).collect();
+    assert_eq!(vec!["Soup", "Salmon", "Salad"], dishes);
+    let cooks: Vec<usize> = first.plates.iter().map(|p| p.cook).collect();
+    assert_eq!(vec![0, 1, 2], cooks);
+    // El pedido está listo cuando lo está su último plato
+    assert_eq!(minutes(15), first.ready_at);
+
+    let ready: Vec<u32> = report.orders.iter().map(|o| o.order).collect();
+    assert_eq!(vec![3, 1, 4, 2], ready);
+}
+
+#[test]
+fn metrics() {
+    let report = KitchenSimulation::new(3).run(dinner_service()).unwrap();
+    let metrics = &report.metrics;
+
+    // Pedido 1: 15 min, 2: 23 min, 3: 11 min y 4: 9 min, esperando a cocineros libres
+    assert_eq!(4, metrics.orders);
+    assert_eq!(7, metrics.dishes);
+    assert_eq!(
+        Duration::from_secs(58 * 60 / 4),
+        metrics.average_ticket_time
+    );
+    assert_eq!(minutes(23), metrics.longest_ticket_time);
+    assert_eq!(minutes(24), metrics.span);
+    assert!((metrics.throughput - 4.0 / (24.0 / 60.0)).abs() < 1e-9);
+
+    let busy: f64 = metrics.utilization.iter().map(|u| u * 24.0).sum();
+    assert!((busy - 58.0).abs() < 1e-9);
+}
+
+#[test]
+fn more_cooks_shorter_tickets() {
+    let times: Vec<Duration> = (1..=4)
+        .map(|cooks| {
+            KitchenSimulation::new(cooks)
+                .run(dinner_service())
+                .unwrap()
+                .metrics
+                .average_ticket_time
+        })
+        .collect();
+
+    assert!(times.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(times[0] > times[3]);
+}
+
+#[test]
+fn results_do_not_depend_on_threads() {
+    let simulation = KitchenSimulation::new(4);
+    let first = simulation.run(dinner_service()).unwrap();
+    for _ in 0..20 {
+        assert_eq!(first, simulation.run(dinner_service()).unwrap());
+    }
+
+    // Ni del orden en que llegan los tickets
+    let mut reversed = dinner_service();
+    reversed.reverse();
+    assert_eq!(first, simulation.run(reversed).unwrap());
+}
+
+#[test]
+fn real_time_pace_gives_the_same_report() {
+    let simulated = KitchenSimulation::new(2).run(dinner_service()).unwrap();
+    let real = KitchenSimulation::new(2)
+        .with_pace(Pace::RealTime { speedup: 60_000 })
+        .run(dinner_service())
+        .unwrap();
+    assert_eq!(simulated, real);
+}
+
+#[test]
+fn empty_kitchen() {
+    let report = KitchenSimulation::new(2)
+        .run(vec![ticket(1, 5, &[])])
+        .unwrap();
+    assert_eq!(1, report.orders.len());
+    assert_eq!(Duration::ZERO, report.orders[0].ticket_time());
+    assert_eq!(0.0, report.metrics.throughput);
+
+    let report = KitchenSimulation::new(2).run(vec![]).unwrap();
+    assert_eq!(0, report.metrics.orders);
+}
+
+#[test]
+fn duplicate_orders_are_refused() {
+    let mut tickets = dinner_service();
+    tickets.push(ticket(3, 12, &[("Salad", 4)]));
+    assert_eq!(
+        Err(RestaurantError::DuplicateOrder(3)),
+        KitchenSimulation::new(2).run(tickets)
+    );
+}
@@ -0,0 +1,128 @@
+use ch07_02_restaurant::house::Breakfast;
+use ch07_02_restaurant::menu::{Category, DietaryTag, Menu, MenuError, Season};
+use chrono::NaiveDate;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+fn menu() -> Menu {
+    Menu::load(concat!(env!("CARGO_MANIFEST_DIR"), "/menu.toml")).unwrap()
+}
+
+#[test]
+fn seasons_by_month() {
+    assert_eq!(Season::Winter, Season::of(date(2, 29)));
+    assert_eq!(Season::Spring, Season::of(date(3, 1)));
+    assert_eq!(Season::Summer, Season::of(date(8, 31)));
+    assert_eq!(Season::Autumn, Season::of(date(11, 30)));
+    assert_eq!(Season::Winter, Season::of(date(12, 1)));
+}
+
+#[test]
+fn load_menu_file() {
+    let menu = menu();
+    assert_eq!(9, menu.items().len());
+
+    let salmon = menu.get("Grilled salmon").unwrap();
+    assert_eq!(1650, salmon.price);
+    assert_eq!(Category::Main, salmon.category);
+    assert!(salmon.seasons.is_empty());
+}
+
+#[test]
+fn availability_depends_on_the_date() {
+    let menu = menu();
+
+    let summer: Vec<&str> = menu
+        .available_on(date(7, 15))
+        .filter(|item| item.category == Category::Appetizer)
+        .map(|item| item.name.as_str())
+        .collect();
+    assert_eq!(vec!["Soup of the day", "Gazpacho", "Salad"], summer);
+
+    let winter: Vec<&str> = menu
+        .available_on(date(1, 15))
+        .filter(|item| item.category == Category::Main)
+        .map(|item| item.name.as_str())
+        .collect();
+    assert_eq!(vec!["Grilled salmon", "Pumpkin risotto"], winter);
+}
+
+#[test]
+fn order_items_from_the_menu() {
+    let menu = menu();
+
+    let item = menu.order_item("Strawberry tart", date(4, 1)).unwrap();
+    assert_eq!(("Strawberry tart", 500), (item.name.as_str(), item.price));
+
+    assert_eq!(
+        Err(MenuError::OutOfSeason(String::from("Strawberry tart"))),
+        menu.order_item("Strawberry tart", date(10, 1))
+    );
+    assert_eq!(
+        Err(MenuError::UnknownItem(String::from("Paella"))),
+        menu.order_item("Paella", date(10, 1))
+    );
+}
+
+#[test]
+fn filter_by_category_and_tag() {
+    let menu = menu();
+    assert_eq!(2, menu.in_category(Category::Dessert).count());
+
+    let vegan: Vec<&str> = menu.with_tag(DietaryTag::Vegan).map(|i| i.name.as_str()).collect();
+    assert_eq!(vec!["Gazpacho", "Salad", "Lemonade"], vegan);
+}
+
+#[test]
+fn json_menu() {
+    let menu = Menu::from_json(
+        r#"{"items": [
+            {"name": "Coffee", "price": 150, "category": "drink", "dietary": ["vegan"]},
+            {"name": "Churros", "price": 300, "category": "breakfast", "seasons": ["winter"]}
+        ]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(vec![DietaryTag::Vegan], menu.get("Coffee").unwrap().dietary);
+    assert_eq!(vec![Season::Winter], menu.get("Churros").unwrap().seasons);
+}
+
+#[test]
+fn invalid_menus() {
+    let duplicated = r#"
+        [[items]]
+        name = "Flan"
+        price = 350
+        category = "dessert"
+
+        [[items]]
+        name = "Flan"
+        price = 400
+        category = "dessert"
+    "#;
+    assert_eq!(
+        Err(MenuError::DuplicateItem(String::from("Flan"))),
+        Menu::from_toml(duplicated)
+    );
+
+    let bad_category = r#"{"items": [{"name": "Soup", "price": 450, "category": "snack"}]}"#;
+    assert!(matches!(Menu::from_json(bad_category), Err(MenuError::Parse(_))));
+
+    let negative_price = r#"{"items": [{"name": "Soup", "price": -1, "category": "main"}]}"#;
+    assert!(matches!(Menu::from_json(negative_price), Err(MenuError::Parse(_))));
+
+    assert!(matches!(Menu::load("missing.toml"), Err(MenuError::Io(_))));
+    assert!(matches!(
+        Menu::load(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs")),
+        Err(MenuError::UnknownFormat(_))
+    ));
+}
+
+#[test]
+fn breakfast_fruit_follows_the_season() {
+    assert_eq!("peaches", Breakfast::summer("Rye").seasonal_fruit());
+    assert_eq!("oranges", Breakfast::in_season("Rye", Season::Winter).seasonal_fruit());
+    assert_eq!(Season::current().fruit(), Breakfast::new("Rye").seasonal_fruit());
+}
@@ -0,0 +1,277 @@
+use ch07_02_restaurant::front_of_house::hosting::{Party, Table};
+use ch07_02_restaurant::front_of_house::reservations::{
+    ManualClock, Policy, ReservationStatus, Scheduler,
+};
+use ch07_02_restaurant::RestaurantError;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+fn at(hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2024, 5, 10)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+fn scheduler(clock: &ManualClock) -> Scheduler<&ManualClock> {
+    Scheduler::new(
+        vec![
+            Table::new(1, 2),
+            Table::new(2, 2),
+            Table::new(3, 4),
+            Table::new(4, 6),
+        ],
+        clock,
+    )
+}
+
+#[test]
+fn smallest_table_that_fits() {
+    let clock = ManualClock::new(at(12, 0));
+    let mut scheduler = scheduler(&clock);
+
+    let id = scheduler.reserve(Party::new("Ana", 3), at(20, 0)).unwrap();
+    let reservation = scheduler.reservation(id).unwrap();
+    assert_eq!(&[3], reservation.tables());
+    assert_eq!(at(21, 30), reservation.end());
+    assert_eq!(ReservationStatus::Booked, reservation.status());
+}
+
+#[test]
+fn overlapping_reservations_conflict() {
+    let clock = ManualClock::new(at(12, 0));
+    let mut scheduler = scheduler(&clock);
+
+    let first = scheduler.reserve(Party::new("Ana", 6), at(20, 0)).unwrap();
+    // La mesa de 6 está ocupada, así que se juntan la de 4 y una de 2
+    let second = scheduler.reserve(Party::new("Bea", 6), at(21, 0)).unwrap();
+    assert_eq!(&[4], scheduler.reservation(first).unwrap().tables());
+    assert_eq!(&[1, 3], scheduler.reservation(second).unwrap().tables());
+
+    assert_eq!(
+        Err(RestaurantError::NoTableAvailable),
+        scheduler.reserve(Party::new("Carla", 4), at(20, 30))
+    );
+    // Cuando termina la primera reserva la mesa vuelve a estar libre
+    let later = scheduler
+        .reserve(Party::new("Carla", 6), at(21, 30))
+        .unwrap();
+    assert_eq!(&[4], scheduler.reservation(later).unwrap().tables());
+}
+
+#[test]
+fn large_parties_combine_tables() {
+    let clock = ManualClock::new(at(12, 0));
+    let mut scheduler = scheduler(&clock);
+
+    let id = scheduler
+        .reserve(Party::new("Everyone", 14), at(20, 0))
+        .unwrap();
+    assert_eq!(&[1, 2, 3, 4], scheduler.reservation(id).unwrap().tables());
+
+    let id = scheduler.reserve(Party::new("Big", 8), at(22, 0)).unwrap();
+    assert_eq!(&[1, 4], scheduler.reservation(id).unwrap().tables());
+
+    assert_eq!(
+        Err(RestaurantError::PartyTooLarge(15)),
+        scheduler.reserve(Party::new("Too many", 15), at(23, 0))
+    );
+}
+
+#[test]
+fn many_free_tables() {
+    // Sin podar habría que probar millones de combinaciones antes de llegar a 20 mesas
+    let clock = ManualClock::new(at(12, 0));
+    let tables = (1..=60).map(|number| Table::new(number, 2)).collect();
+    let mut scheduler = Scheduler::new(tables, &clock);
+
+    let id = scheduler.reserve(Party::new("Ana", 39), at(20, 0)).unwrap();
+    let reserved = scheduler.reservation(id).unwrap().tables();
+    assert_eq!((1..=20).collect::<Vec<_>>(), reserved);
+}
+
+#[test]
+fn invalid_reservations() {
+    let clock = ManualClock::new(at(12, 0));
+    let mut scheduler = scheduler(&clock);
+
+    assert_eq!(
+        Err(RestaurantError::InThePast),
+        scheduler.reserve(Party::new("Ana", 2), at(11, 45))
+    );
+    assert_eq!(
+        Err(RestaurantError::NotOnSlot),
+        scheduler.reserve(Party::new("Ana", 2), at(20, 10))
+    );
+    assert_eq!(
+        Err(RestaurantError::EmptyParty),
+        scheduler.reserve(Party::new("Nobody", 0), at(20, 0))
+    );
+    assert_eq!(
+        Err(RestaurantError::UnknownReservation(7)),
+        scheduler.cancel(7)
+    );
+}
+
+#[test]
+fn check_in_and_finish() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    let id = scheduler.reserve(Party::new("Ana", 2), at(20, 0)).unwrap();
+
+    clock.set(at(20, 10));
+    assert_eq!(Ok(vec![1]), scheduler.check_in(id));
+    assert_eq!(
+        Err(RestaurantError::WrongReservationStatus(id)),
+        scheduler.check_in(id)
+    );
+    assert!(!scheduler.free_tables().contains(&1));
+
+    // Se quedan más de lo previsto: la mesa sigue ocupada
+    clock.set(at(22, 0));
+    assert!(!scheduler.free_tables().contains(&1));
+
+    scheduler.finish(id).unwrap();
+    assert_eq!(vec![1, 2, 3, 4], scheduler.free_tables());
+    assert_eq!(at(22, 0), scheduler.reservation(id).unwrap().end());
+}
+
+#[test]
+fn seated_parties_sit_at_the_host_tables() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    let first = scheduler.reserve(Party::new("Ana", 6), at(19, 0)).unwrap();
+    let second = scheduler.reserve(Party::new("Bea", 6), at(20, 45)).unwrap();
+    scheduler.add_to_waitlist(Party::new("Carla", 3)).unwrap();
+
+    scheduler.check_in(first).unwrap();
+    scheduler.seat_walk_ins();
+    let host = scheduler.host();
+    assert_eq!("Ana", host.table(4).unwrap().party().unwrap().name);
+    assert_eq!("Carla", host.table(3).unwrap().party().unwrap().name);
+    assert_eq!(0, host.waitlist().count());
+
+    // Ana se queda más de lo previsto y Bea tiene que esperar
+    clock.set(at(20, 45));
+    assert_eq!(
+        Err(RestaurantError::NoTableAvailable),
+        scheduler.check_in(second)
+    );
+    scheduler.finish(first).unwrap();
+    assert!(scheduler.host().table(4).unwrap().is_free());
+    assert_eq!(Ok(vec![4]), scheduler.check_in(second));
+    assert_eq!(
+        "Bea",
+        scheduler.host().table(4).unwrap().party().unwrap().name
+    );
+}
+
+#[test]
+fn early_arrival_needs_free_tables() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    assert_eq!(Ok(1), scheduler.add_to_waitlist(Party::new("Walk-in", 2)));
+    let walk_in = scheduler.seat_walk_ins()[0];
+    assert_eq!(&[1], scheduler.reservation(walk_in).unwrap().tables());
+
+    // Se espera que el walk-in se vaya a las 20:30
+    let id = scheduler.reserve(Party::new("Ana", 2), at(21, 0)).unwrap();
+    assert_eq!(&[1], scheduler.reservation(id).unwrap().tables());
+
+    clock.set(at(20, 0));
+    assert_eq!(
+        Err(RestaurantError::NoTableAvailable),
+        scheduler.check_in(id)
+    );
+
+    scheduler.finish(walk_in).unwrap();
+    assert_eq!(Ok(vec![1]), scheduler.check_in(id));
+    assert_eq!(at(20, 0), scheduler.reservation(id).unwrap().start());
+}
+
+#[test]
+fn no_shows_free_their_tables() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    let id = scheduler.reserve(Party::new("Ana", 6), at(20, 0)).unwrap();
+
+    clock.set(at(20, 14));
+    assert!(scheduler.release_no_shows().is_empty());
+
+    clock.set(at(20, 15));
+    assert_eq!(vec![id], scheduler.release_no_shows());
+    assert_eq!(
+        ReservationStatus::NoShow,
+        scheduler.reservation(id).unwrap().status()
+    );
+    assert_eq!(
+        Err(RestaurantError::WrongReservationStatus(id)),
+        scheduler.check_in(id)
+    );
+
+    scheduler.add_to_waitlist(Party::new("Walk-in", 6)).unwrap();
+    let seated = scheduler.seat_walk_ins();
+    assert_eq!(&[4], scheduler.reservation(seated[0]).unwrap().tables());
+}
+
+#[test]
+fn expired_no_shows_do_not_block_new_bookings() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    let id = scheduler.reserve(Party::new("Ana", 6), at(20, 0)).unwrap();
+
+    // Nadie ha llamado a release_no_shows, pero la mesa ya no está reservada
+    clock.set(at(20, 15));
+    assert!(scheduler.free_tables().contains(&4));
+    let later = scheduler.reserve(Party::new("Bea", 6), at(20, 30)).unwrap();
+    assert_eq!(&[4], scheduler.reservation(later).unwrap().tables());
+    assert_eq!(
+        ReservationStatus::Booked,
+        scheduler.reservation(id).unwrap().status()
+    );
+}
+
+#[test]
+fn walk_ins_do_not_take_reserved_tables() {
+    let clock = ManualClock::new(at(19, 0));
+    let mut scheduler = scheduler(&clock);
+    scheduler.reserve(Party::new("Ana", 6), at(20, 0)).unwrap();
+    scheduler.reserve(Party::new("Bea", 4), at(20, 0)).unwrap();
+
+    scheduler.add_to_waitlist(Party::new("Carla", 5)).unwrap();
+    scheduler.add_to_waitlist(Party::new("Dani", 2)).unwrap();
+
+    // Carla no cabe hasta las 21:30, pero Dani sí
+    let seated = scheduler.seat_walk_ins();
+    assert_eq!(1, seated.len());
+    assert_eq!(
+        "Dani",
+        scheduler.reservation(seated[0]).unwrap().party().name
+    );
+    let waiting: Vec<&str> = scheduler.waitlist().map(|p| p.name.as_str()).collect();
+    assert_eq!(vec!["Carla"], waiting);
+
+    clock.set(at(21, 30));
+    assert_eq!(1, scheduler.seat_walk_ins().len());
+    assert_eq!(0, scheduler.waitlist().count());
+}
+
+#[test]
+fn custom_policy() {
+    let clock = ManualClock::new(at(12, 0));
+    let policy = Policy {
+        slot: Duration::minutes(30),
+        dining: Duration::minutes(60),
+        no_show_after: Duration::minutes(5),
+    };
+    let mut scheduler = Scheduler::with_policy(vec![Table::new(1, 4)], &clock, policy);
+
+    assert_eq!(
+        Err(RestaurantError::NotOnSlot),
+        scheduler.reserve(Party::new("Ana", 2), at(20, 15))
+    );
+    scheduler.reserve(Party::new("Ana", 2), at(20, 0)).unwrap();
+    assert!(scheduler.reserve(Party::new("Bea", 2), at(21, 0)).is_ok());
+
+    clock.set(at(20, 5));
+    assert_eq!(1, scheduler.release_no_shows().len());
+}
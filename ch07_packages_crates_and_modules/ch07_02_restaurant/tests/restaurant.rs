@@ -0,0 +1,137 @@
+use ch07_02_restaurant::front_of_house::hosting::{Party, Table};
+use ch07_02_restaurant::front_of_house::serving::{OrderItem, OrderStatus};
+use ch07_02_restaurant::{Restaurant, RestaurantError};
+
+// Integration tests: testean la interfaz pública del restaurante
+fn restaurant() -> Restaurant {
+    Restaurant::new(vec![Table::new(1, 2), Table::new(2, 4), Table::new(3, 6)])
+}
+
+#[test]
+fn full_service() {
+    let mut restaurant = restaurant();
+
+    assert_eq!(Ok(1), restaurant.add_to_waitlist(Party::new("Ferris", 2)));
+    let (table, party) = restaurant.sit_at_table().unwrap();
+    assert_eq!(1, table);
+    assert_eq!("Ferris", party.name);
+
+    let soup = restaurant
+        .take_order(table, vec![OrderItem::new("Soup", 450), OrderItem::new("Salad", 600)])
+        .unwrap();
+    let dessert = restaurant.take_order(table, vec![OrderItem::new("Flan", 350)]).unwrap();
+
+    assert_eq!(Some(soup), restaurant.cook_order());
+    restaurant.serve_order(soup).unwrap();
+    assert_eq!(Some(dessert), restaurant.cook_order());
+    restaurant.serve_order(dessert).unwrap();
+
+    let bill = restaurant.take_payment(table).unwrap();
+    assert_eq!(vec![soup, dessert], bill.orders);
+    assert_eq!(1400, bill.total());
+    assert!(restaurant.host().table(table).unwrap().is_free());
+    assert_eq!(OrderStatus::Paid, restaurant.order(soup).unwrap().status());
+}
+
+#[test]
+fn waitlist_keeps_arrival_order() {
+    let mut restaurant = restaurant();
+
+    restaurant.add_to_waitlist(Party::new("Ana", 4)).unwrap();
+    restaurant.add_to_waitlist(Party::new("Bea", 4)).unwrap();
+    assert_eq!(Ok(3), restaurant.add_to_waitlist(Party::new("Carla", 4)));
+
+    let names: Vec<&str> = restaurant.host().waitlist().map(|p| p.name.as_str()).collect();
+    assert_eq!(vec!["Ana", "Bea", "Carla"], names);
+
+    assert_eq!(2, restaurant.sit_at_table().unwrap().0);
+    assert_eq!(3, restaurant.sit_at_table().unwrap().0);
+    // Ya no queda ninguna mesa donde quepan 4
+    assert_eq!(None, restaurant.sit_at_table());
+    assert_eq!(1, restaurant.host().waitlist().count());
+}
+
+#[test]
+fn small_parties_are_not_blocked_by_large_ones() {
+    let mut restaurant = restaurant();
+    restaurant.add_to_waitlist(Party::new("Big", 6)).unwrap();
+    restaurant.sit_at_table().unwrap();
+
+    restaurant.add_to_waitlist(Party::new("Also big", 5)).unwrap();
+    restaurant.add_to_waitlist(Party::new("Couple", 2)).unwrap();
+
+    let (table, party) = restaurant.sit_at_table().unwrap();
+    assert_eq!((1, "Couple"), (table, party.name.as_str()));
+}
+
+#[test]
+fn invalid_parties() {
+    let mut restaurant = restaurant();
+    assert_eq!(
+        Err(RestaurantError::PartyTooLarge(7)),
+        restaurant.add_to_waitlist(Party::new("Huge", 7))
+    );
+    assert_eq!(
+        Err(RestaurantError::EmptyParty),
+        restaurant.add_to_waitlist(Party::new("Nobody", 0))
+    );
+}
+
+#[test]
+fn orders_need_an_occupied_table() {
+    let mut restaurant = restaurant();
+    let items = vec![OrderItem::new("Soup", 450)];
+
+    assert_eq!(
+        Err(RestaurantError::TableNotOccupied(1)),
+        restaurant.take_order(1, items.clone())
+    );
+    assert_eq!(Err(RestaurantError::UnknownTable(9)), restaurant.take_order(9, items));
+
+    restaurant.add_to_waitlist(Party::new("Ferris", 1)).unwrap();
+    restaurant.sit_at_table().unwrap();
+    assert_eq!(Err(RestaurantError::EmptyOrder), restaurant.take_order(1, vec![]));
+}
+
+#[test]
+fn orders_follow_their_status() {
+    let mut restaurant = restaurant();
+    restaurant.add_to_waitlist(Party::new("Ferris", 1)).unwrap();
+    restaurant.sit_at_table().unwrap();
+    let order = restaurant.take_order(1, vec![OrderItem::new("Soup", 450)]).unwrap();
+
+    // No se puede servir ni cobrar lo que no está cocinado
+    assert_eq!(Err(RestaurantError::WrongOrderStatus(order)), restaurant.serve_order(order));
+    assert_eq!(Err(RestaurantError::OrdersPending(1)), restaurant.take_payment(1));
+    assert_eq!(Err(RestaurantError::UnknownOrder(99)), restaurant.serve_order(99));
+
+    restaurant.cook_order().unwrap();
+    assert_eq!(OrderStatus::Ready, restaurant.order(order).unwrap().status());
+    assert_eq!(None, restaurant.cook_order());
+}
+
+#[test]
+fn incorrect_orders_are_cooked_first() {
+    let mut restaurant = restaurant();
+    restaurant.add_to_waitlist(Party::new("Ferris", 2)).unwrap();
+    restaurant.sit_at_table().unwrap();
+
+    let first = restaurant.take_order(1, vec![OrderItem::new("Soup", 450)]).unwrap();
+    restaurant.cook_order().unwrap();
+    restaurant.serve_order(first).unwrap();
+    let second = restaurant.take_order(1, vec![OrderItem::new("Flan", 350)]).unwrap();
+
+    restaurant.fix_incorrect_order(first).unwrap();
+    let queue: Vec<u32> = restaurant.kitchen().queue().copied().collect();
+    assert_eq!(vec![first, second], queue);
+    assert_eq!(Err(RestaurantError::WrongOrderStatus(first)), restaurant.fix_incorrect_order(first));
+}
+
+#[test]
+fn nothing_to_pay() {
+    let mut restaurant = restaurant();
+    restaurant.add_to_waitlist(Party::new("Ferris", 2)).unwrap();
+    restaurant.sit_at_table().unwrap();
+
+    assert_eq!(Err(RestaurantError::NothingToPay(1)), restaurant.take_payment(1));
+}
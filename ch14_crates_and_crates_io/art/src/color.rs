@@ -0,0 +1,325 @@
+//! Numeric representations of colors: RGB, HSL and `#rrggbb` hex.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::kinds::*;
+
+/// A color with 8-bit red, green and blue channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// A color in the HSL model.
+///
+/// `h` is the hue in degrees, in `0.0..360.0`, while `s` (saturation) and
+/// `l` (lightness) go from `0.0` to `1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f64,
+    pub s: f64,
+    pub l: f64,
+}
+
+/// The error returned when a string is not a valid `#rrggbb` hex color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// The string doesn't start with `#`.
+    MissingHash,
+    /// The string doesn't have exactly six hex digits after the `#`.
+    InvalidLength(usize),
+    /// The string contains a character that isn't a hex digit.
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseColorError::MissingHash => write!(f, "hex colors must start with '#'"),
+            ParseColorError::InvalidLength(len) => {
+                write!(f, "expected 6 hex digits, found {}", len)
+            }
+            ParseColorError::InvalidDigit(c) => write!(f, "'{}' is not a hex digit", c),
+        }
+    }
+}
+
+impl Error for ParseColorError {}
+
+impl Color {
+    /// Creates a color from its red, green and blue channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b }
+    }
+
+    /// Parses a `#rrggbb` hex color. Digits can be upper or lower case.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::Color;
+    ///
+    /// let color = Color::from_hex("#ff8800").unwrap();
+    /// assert_eq!(Color::rgb(255, 136, 0), color);
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Color, ParseColorError> {
+        let digits = hex.strip_prefix('#').ok_or(ParseColorError::MissingHash)?;
+
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(ParseColorError::InvalidDigit(c));
+        }
+        if digits.len() != 6 {
+            return Err(ParseColorError::InvalidLength(digits.len()));
+        }
+
+        // Todos los caracteres son ASCII, así que se puede cortar por bytes.
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+        Ok(Color::rgb(channel(0), channel(2), channel(4)))
+    }
+
+    /// Formats the color as lower case `#rrggbb` hex.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Converts an HSL color to RGB. Out of range values are wrapped (hue)
+    /// or clamped (saturation and lightness).
+    pub fn from_hsl(hsl: Hsl) -> Color {
+        let h = hsl.h.rem_euclid(360.0);
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = chroma * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+        let m = l - chroma / 2.0;
+
+        let (r, g, b) = match h {
+            h if h < 60.0 => (chroma, x, 0.0),
+            h if h < 120.0 => (x, chroma, 0.0),
+            h if h < 180.0 => (0.0, chroma, x),
+            h if h < 240.0 => (0.0, x, chroma),
+            h if h < 300.0 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+        Color::rgb(channel(r), channel(g), channel(b))
+    }
+
+    /// Converts the color to HSL.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::Color;
+    ///
+    /// let hsl = Color::rgb(0, 0, 255).to_hsl();
+    /// assert_eq!((240.0, 1.0, 0.5), (hsl.h, hsl.s, hsl.l));
+    /// ```
+    pub fn to_hsl(self) -> Hsl {
+        let r = self.r as f64 / 255.0;
+        let g = self.g as f64 / 255.0;
+        let b = self.b as f64 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+
+        // Grises: sin croma no hay tono ni saturación.
+        if delta == 0.0 {
+            return Hsl { h: 0.0, s: 0.0, l };
+        }
+
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        Hsl { h, s, l }
+    }
+
+    /// Finds the named RYB color closest to this one, measured as the
+    /// euclidean distance between their RGB channels.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::{Color, Mixture, SecondaryColor};
+    ///
+    /// let nearest = Color::rgb(240, 150, 20).nearest_named();
+    /// assert_eq!(Mixture::Secondary(SecondaryColor::Orange), nearest);
+    /// ```
+    pub fn nearest_named(self) -> Mixture {
+        Mixture::ALL
+            .into_iter()
+            .min_by_key(|&named| self.distance_squared(Color::from(named)))
+            .unwrap()
+    }
+
+    fn distance_squared(self, other: Color) -> u32 {
+        let diff = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+        diff(self.r, other.r) + diff(self.g, other.g) + diff(self.b, other.b)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::from_hex(s)
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Color {
+        Color::from_hsl(hsl)
+    }
+}
+
+impl From<Color> for Hsl {
+    fn from(color: Color) -> Hsl {
+        color.to_hsl()
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Color {
+        Color::rgb(r, g, b)
+    }
+}
+
+impl From<Color> for (u8, u8, u8) {
+    fn from(color: Color) -> (u8, u8, u8) {
+        (color.r, color.g, color.b)
+    }
+}
+
+/* Valores RGB aproximados de la rueda de color RYB de los pintores.
+ * No hay una conversión exacta entre RYB y RGB, así que se usa una tabla.
+ */
+impl From<PrimaryColor> for Color {
+    fn from(color: PrimaryColor) -> Color {
+        match color {
+            PrimaryColor::Red => Color::rgb(0xfe, 0x27, 0x12),
+            PrimaryColor::Yellow => Color::rgb(0xfe, 0xfe, 0x33),
+            PrimaryColor::Blue => Color::rgb(0x02, 0x47, 0xfe),
+        }
+    }
+}
+
+impl From<SecondaryColor> for Color {
+    fn from(color: SecondaryColor) -> Color {
+        match color {
+            SecondaryColor::Orange => Color::rgb(0xfb, 0x99, 0x02),
+            SecondaryColor::Green => Color::rgb(0x66, 0xb0, 0x32),
+            SecondaryColor::Purple => Color::rgb(0x86, 0x01, 0xaf),
+        }
+    }
+}
+
+impl From<TertiaryColor> for Color {
+    fn from(color: TertiaryColor) -> Color {
+        match color {
+            TertiaryColor::RedOrange => Color::rgb(0xfc, 0x60, 0x0a),
+            TertiaryColor::YellowOrange => Color::rgb(0xfc, 0xcc, 0x1a),
+            TertiaryColor::YellowGreen => Color::rgb(0xb2, 0xd7, 0x32),
+            TertiaryColor::BlueGreen => Color::rgb(0x34, 0x7c, 0x98),
+            TertiaryColor::BluePurple => Color::rgb(0x44, 0x24, 0xd6),
+            TertiaryColor::RedPurple => Color::rgb(0xc2, 0x14, 0x60),
+        }
+    }
+}
+
+impl From<Mixture> for Color {
+    fn from(mixture: Mixture) -> Color {
+        match mixture {
+            Mixture::Primary(color) => color.into(),
+            Mixture::Secondary(color) => color.into(),
+            Mixture::Tertiary(color) => color.into(),
+            Mixture::Neutral => Color::rgb(0x8b, 0x5a, 0x2b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let color = Color::rgb(18, 171, 255);
+        assert_eq!("#12abff", color.to_hex());
+        assert_eq!(Ok(color), Color::from_hex("#12abff"));
+        assert_eq!(Ok(color), "#12ABFF".parse());
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(Err(ParseColorError::MissingHash), Color::from_hex("ff8800"));
+        assert_eq!(Err(ParseColorError::InvalidLength(3)), Color::from_hex("#f80"));
+        assert_eq!(Err(ParseColorError::InvalidDigit('g')), Color::from_hex("#gg8800"));
+        assert_eq!(Err(ParseColorError::InvalidDigit('é')), Color::from_hex("#ff88é"));
+    }
+
+    #[test]
+    fn hsl_of_known_colors() {
+        let cases = [
+            (Color::rgb(0, 0, 0), (0.0, 0.0, 0.0)),
+            (Color::rgb(255, 255, 255), (0.0, 0.0, 1.0)),
+            (Color::rgb(255, 0, 0), (0.0, 1.0, 0.5)),
+            (Color::rgb(0, 255, 0), (120.0, 1.0, 0.5)),
+            (Color::rgb(0, 0, 255), (240.0, 1.0, 0.5)),
+            (Color::rgb(255, 0, 255), (300.0, 1.0, 0.5)),
+        ];
+
+        for (color, (h, s, l)) in cases {
+            assert_eq!(Hsl { h, s, l }, color.to_hsl(), "{}", color);
+            assert_eq!(color, Color::from_hsl(Hsl { h, s, l }));
+        }
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let color = Color::rgb(r, g, b);
+                    assert_eq!(color, Color::from(Hsl::from(color)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hsl_out_of_range_values() {
+        let red = Color::rgb(255, 0, 0);
+        assert_eq!(red, Color::from_hsl(Hsl { h: 360.0, s: 1.0, l: 0.5 }));
+        assert_eq!(red, Color::from_hsl(Hsl { h: -720.0, s: 2.0, l: 0.5 }));
+    }
+
+    #[test]
+    fn named_colors_are_their_own_nearest() {
+        for named in Mixture::ALL {
+            assert_eq!(named, Color::from(named).nearest_named());
+        }
+    }
+
+    #[test]
+    fn nearest_named_of_pure_colors() {
+        assert_eq!(Mixture::Primary(PrimaryColor::Red), Color::rgb(255, 0, 0).nearest_named());
+        assert_eq!(Mixture::Primary(PrimaryColor::Blue), Color::rgb(0, 0, 255).nearest_named());
+        assert_eq!(Mixture::Neutral, Color::rgb(120, 80, 40).nearest_named());
+    }
+}
@@ -1,26 +1,201 @@
 //! # Art
-//! 
+//!
 //! A library for modeling artistic concepts
 
+pub mod color;
+pub mod palette;
+pub mod pigment;
+
 // Re-exports
+pub use self::color::Color;
+pub use self::kinds::Mixture;
 pub use self::kinds::PrimaryColor;
 pub use self::kinds::SecondaryColor;
+pub use self::kinds::TertiaryColor;
+pub use self::pigment::mix_pigments;
 pub use self::utils::mix;
+pub use self::utils::mix_with_secondary;
 
 pub mod kinds {
+    use std::fmt;
+    use std::str::FromStr;
+
     /// The primary colors according to the RYB color model.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub enum PrimaryColor {
         Red,
         Yellow,
         Blue,
     }
 
+    impl PrimaryColor {
+        /// Every primary color, in color wheel order.
+        pub const ALL: [PrimaryColor; 3] = [
+            PrimaryColor::Red,
+            PrimaryColor::Yellow,
+            PrimaryColor::Blue,
+        ];
+    }
+
     /// The secondary colors according to the RYB color model.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub enum SecondaryColor {
         Orange,
         Green,
         Purple,
     }
+
+    impl SecondaryColor {
+        /// Every secondary color, in color wheel order.
+        pub const ALL: [SecondaryColor; 3] = [
+            SecondaryColor::Orange,
+            SecondaryColor::Green,
+            SecondaryColor::Purple,
+        ];
+    }
+
+    /// The tertiary colors according to the RYB color model, made by
+    /// mixing a primary color with an adjacent secondary color.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum TertiaryColor {
+        RedOrange,
+        YellowOrange,
+        YellowGreen,
+        BlueGreen,
+        BluePurple,
+        RedPurple,
+    }
+
+    impl TertiaryColor {
+        /// Every tertiary color, in color wheel order.
+        pub const ALL: [TertiaryColor; 6] = [
+            TertiaryColor::RedOrange,
+            TertiaryColor::YellowOrange,
+            TertiaryColor::YellowGreen,
+            TertiaryColor::BlueGreen,
+            TertiaryColor::BluePurple,
+            TertiaryColor::RedPurple,
+        ];
+    }
+
+    /// The result of mixing colors.
+    ///
+    /// Mixing a color with itself gives back the same color, and mixing two
+    /// complementary colors cancels out their hues into a `Neutral` brown.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Mixture {
+        Primary(PrimaryColor),
+        Secondary(SecondaryColor),
+        Tertiary(TertiaryColor),
+        Neutral,
+    }
+
+    impl Mixture {
+        /// Every named mixture: the twelve hues of the color wheel, starting
+        /// at red, followed by `Neutral`.
+        pub const ALL: [Mixture; 13] = [
+            Mixture::Primary(PrimaryColor::Red),
+            Mixture::Tertiary(TertiaryColor::RedOrange),
+            Mixture::Secondary(SecondaryColor::Orange),
+            Mixture::Tertiary(TertiaryColor::YellowOrange),
+            Mixture::Primary(PrimaryColor::Yellow),
+            Mixture::Tertiary(TertiaryColor::YellowGreen),
+            Mixture::Secondary(SecondaryColor::Green),
+            Mixture::Tertiary(TertiaryColor::BlueGreen),
+            Mixture::Primary(PrimaryColor::Blue),
+            Mixture::Tertiary(TertiaryColor::BluePurple),
+            Mixture::Secondary(SecondaryColor::Purple),
+            Mixture::Tertiary(TertiaryColor::RedPurple),
+            Mixture::Neutral,
+        ];
+
+        /// Position of the hue on the twelve-step color wheel, with red at
+        /// `0`. `Neutral` has no hue, so it has no position.
+        pub fn wheel_position(self) -> Option<usize> {
+            match self {
+                Mixture::Neutral => None,
+                hue => Mixture::ALL.iter().position(|&named| named == hue),
+            }
+        }
+
+        /// The hue at the given position of the color wheel, wrapping
+        /// around after twelve steps.
+        pub fn from_wheel_position(position: usize) -> Mixture {
+            Mixture::ALL[position % 12]
+        }
+    }
+
+    impl fmt::Display for PrimaryColor {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                PrimaryColor::Red => "red",
+                PrimaryColor::Yellow => "yellow",
+                PrimaryColor::Blue => "blue",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl fmt::Display for SecondaryColor {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                SecondaryColor::Orange => "orange",
+                SecondaryColor::Green => "green",
+                SecondaryColor::Purple => "purple",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl fmt::Display for TertiaryColor {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                TertiaryColor::RedOrange => "red-orange",
+                TertiaryColor::YellowOrange => "yellow-orange",
+                TertiaryColor::YellowGreen => "yellow-green",
+                TertiaryColor::BlueGreen => "blue-green",
+                TertiaryColor::BluePurple => "blue-purple",
+                TertiaryColor::RedPurple => "red-purple",
+            };
+            f.write_str(name)
+        }
+    }
+
+    impl fmt::Display for Mixture {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Mixture::Primary(color) => color.fmt(f),
+                Mixture::Secondary(color) => color.fmt(f),
+                Mixture::Tertiary(color) => color.fmt(f),
+                Mixture::Neutral => f.write_str("neutral"),
+            }
+        }
+    }
+
+    /// The error returned when parsing an unknown color name.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ParseMixtureError(String);
+
+    impl fmt::Display for ParseMixtureError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "unknown color name '{}'", self.0)
+        }
+    }
+
+    impl std::error::Error for ParseMixtureError {}
+
+    impl FromStr for Mixture {
+        type Err = ParseMixtureError;
+
+        /// Parses a color name as shown by `Display`, like `"red-orange"`,
+        /// ignoring case.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Mixture::ALL
+                .into_iter()
+                .find(|named| named.to_string().eq_ignore_ascii_case(s))
+                .ok_or_else(|| ParseMixtureError(s.to_string()))
+        }
+    }
 }
 
 pub mod utils {
@@ -28,7 +203,135 @@ pub mod utils {
 
     /// Combines two primary colors in equal amounts to create
     /// a secondary color.
-    pub fn mix(c1: PrimaryColor, c2: PrimaryColor) -> SecondaryColor {
-        SecondaryColor::Orange
+    ///
+    /// Mixing a primary color with itself gives back that primary color.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::{mix, Mixture, PrimaryColor, SecondaryColor};
+    ///
+    /// let orange = mix(PrimaryColor::Red, PrimaryColor::Yellow);
+    /// assert_eq!(Mixture::Secondary(SecondaryColor::Orange), orange);
+    /// ```
+    pub fn mix(c1: PrimaryColor, c2: PrimaryColor) -> Mixture {
+        use PrimaryColor::*;
+
+        match (c1, c2) {
+            (Red, Red) => Mixture::Primary(Red),
+            (Yellow, Yellow) => Mixture::Primary(Yellow),
+            (Blue, Blue) => Mixture::Primary(Blue),
+            (Red, Yellow) | (Yellow, Red) => Mixture::Secondary(SecondaryColor::Orange),
+            (Yellow, Blue) | (Blue, Yellow) => Mixture::Secondary(SecondaryColor::Green),
+            (Blue, Red) | (Red, Blue) => Mixture::Secondary(SecondaryColor::Purple),
+        }
     }
-}
\ No newline at end of file
+
+    /// Combines a primary color and a secondary color in equal amounts.
+    ///
+    /// A secondary color adjacent to the primary on the color wheel gives a
+    /// tertiary color, while its complement gives a `Neutral` mixture.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::{mix_with_secondary, Mixture, PrimaryColor, SecondaryColor, TertiaryColor};
+    ///
+    /// let mixture = mix_with_secondary(PrimaryColor::Blue, SecondaryColor::Green);
+    /// assert_eq!(Mixture::Tertiary(TertiaryColor::BlueGreen), mixture);
+    /// ```
+    pub fn mix_with_secondary(primary: PrimaryColor, secondary: SecondaryColor) -> Mixture {
+        use PrimaryColor::*;
+        use SecondaryColor::*;
+
+        match (primary, secondary) {
+            (Red, Orange) => Mixture::Tertiary(TertiaryColor::RedOrange),
+            (Red, Purple) => Mixture::Tertiary(TertiaryColor::RedPurple),
+            (Yellow, Orange) => Mixture::Tertiary(TertiaryColor::YellowOrange),
+            (Yellow, Green) => Mixture::Tertiary(TertiaryColor::YellowGreen),
+            (Blue, Green) => Mixture::Tertiary(TertiaryColor::BlueGreen),
+            (Blue, Purple) => Mixture::Tertiary(TertiaryColor::BluePurple),
+            (Red, Green) | (Yellow, Purple) | (Blue, Orange) => Mixture::Neutral,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PrimaryColor::*;
+    use SecondaryColor::*;
+    use TertiaryColor::*;
+
+    #[test]
+    fn mix_every_pair_of_primaries() {
+        let expected = [
+            (Red, Red, Mixture::Primary(Red)),
+            (Red, Yellow, Mixture::Secondary(Orange)),
+            (Red, Blue, Mixture::Secondary(Purple)),
+            (Yellow, Red, Mixture::Secondary(Orange)),
+            (Yellow, Yellow, Mixture::Primary(Yellow)),
+            (Yellow, Blue, Mixture::Secondary(Green)),
+            (Blue, Red, Mixture::Secondary(Purple)),
+            (Blue, Yellow, Mixture::Secondary(Green)),
+            (Blue, Blue, Mixture::Primary(Blue)),
+        ];
+
+        for (c1, c2, mixture) in expected {
+            assert_eq!(mixture, mix(c1, c2), "mixing {:?} with {:?}", c1, c2);
+        }
+    }
+
+    #[test]
+    fn parse_color_names() {
+        for named in Mixture::ALL {
+            assert_eq!(Ok(named), named.to_string().parse());
+        }
+        assert_eq!(Ok(Mixture::Tertiary(BlueGreen)), "Blue-Green".parse());
+        assert!("magenta".parse::<Mixture>().is_err());
+    }
+
+    #[test]
+    fn mix_is_commutative() {
+        for c1 in PrimaryColor::ALL {
+            for c2 in PrimaryColor::ALL {
+                assert_eq!(mix(c1, c2), mix(c2, c1));
+            }
+        }
+    }
+
+    #[test]
+    fn mix_primary_with_every_secondary() {
+        let expected = [
+            (Red, Orange, Mixture::Tertiary(RedOrange)),
+            (Red, Green, Mixture::Neutral),
+            (Red, Purple, Mixture::Tertiary(RedPurple)),
+            (Yellow, Orange, Mixture::Tertiary(YellowOrange)),
+            (Yellow, Green, Mixture::Tertiary(YellowGreen)),
+            (Yellow, Purple, Mixture::Neutral),
+            (Blue, Orange, Mixture::Neutral),
+            (Blue, Green, Mixture::Tertiary(BlueGreen)),
+            (Blue, Purple, Mixture::Tertiary(BluePurple)),
+        ];
+
+        for (primary, secondary, mixture) in expected {
+            assert_eq!(
+                mixture,
+                mix_with_secondary(primary, secondary),
+                "mixing {:?} with {:?}",
+                primary,
+                secondary
+            );
+        }
+    }
+
+    #[test]
+    fn every_tertiary_color_can_be_mixed() {
+        for tertiary in TertiaryColor::ALL {
+            let found = PrimaryColor::ALL.iter().any(|&p| {
+                SecondaryColor::ALL
+                    .iter()
+                    .any(|&s| mix_with_secondary(p, s) == Mixture::Tertiary(tertiary))
+            });
+            assert!(found, "{:?} is not reachable by mixing", tertiary);
+        }
+    }
+}
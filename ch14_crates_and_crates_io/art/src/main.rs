@@ -3,11 +3,328 @@
 // use art::utils::mix;
 
 // Con re-export
-use art::PrimaryColor;
 use art::mix;
+use art::mix_with_secondary;
+use art::palette::{Palette, Scheme};
+use art::{mix_pigments, Color, Mixture};
+
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::process;
+
+const USAGE: &str = "usage:
+    art mix <color> <color>...          colors can be weighted, like red:2
+    art convert <color> [--to rgb|hsl|hex|name]
+    art palette <color> [--scheme <scheme>] [--format css|gpl|json]
+
+colors are names (red, blue-green, ...) or hex values (#ff8800)";
+
+/* Argumentos ya interpretados. Como en minigrep, se validan todos antes de
+ * ejecutar nada y un error termina el programa con un mensaje por stderr.
+ */
+#[derive(Debug, PartialEq)]
+enum Command {
+    Mix(Vec<(String, f64)>),
+    Convert { color: String, to: Option<String> },
+    Palette { base: Mixture, scheme: Scheme, format: Option<String> },
+}
+
+impl Command {
+    fn new(args: &[String]) -> Result<Command, String> {
+        let (name, rest) = args.split_first().ok_or("missing command")?;
+
+        match name.as_str() {
+            "mix" => {
+                if rest.len() < 2 {
+                    return Err("mix needs at least two colors".to_string());
+                }
+                let parts = rest.iter().map(|arg| parse_part(arg)).collect::<Result<_, _>>()?;
+                Ok(Command::Mix(parts))
+            }
+            "convert" => {
+                let (color, flags) = rest.split_first().ok_or("missing color to convert")?;
+                let to = options(flags, &["--to"])?.remove("--to");
+                if let Some(format) = &to {
+                    if !["rgb", "hsl", "hex", "name"].contains(&format.as_str()) {
+                        return Err(format!("unknown format '{}'", format));
+                    }
+                }
+                Ok(Command::Convert { color: color.clone(), to })
+            }
+            "palette" => {
+                let (base, flags) = rest.split_first().ok_or("missing base color")?;
+                let base: Mixture = base.parse().map_err(|e| format!("{}", e))?;
+                let mut options = options(flags, &["--scheme", "--format"])?;
+                let scheme = match options.remove("--scheme") {
+                    Some(scheme) => scheme.parse().map_err(|e| format!("{}", e))?,
+                    None => Scheme::Complementary,
+                };
+                let format = options.remove("--format");
+                if let Some(format) = &format {
+                    if !["css", "gpl", "json"].contains(&format.as_str()) {
+                        return Err(format!("unknown format '{}'", format));
+                    }
+                }
+                Ok(Command::Palette { base, scheme, format })
+            }
+            other => Err(format!("unknown command '{}'", other)),
+        }
+    }
+}
+
+// Lee las opciones "--nombre valor". Las que no están en `allowed` son un error.
+fn options(args: &[String], allowed: &[&str]) -> Result<HashMap<String, String>, String> {
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        if !allowed.contains(&flag.as_str()) {
+            return Err(format!("unexpected argument '{}'", flag));
+        }
+        let value = args.next().ok_or(format!("missing value for {}", flag))?;
+        options.insert(flag.clone(), value.clone());
+    }
+
+    Ok(options)
+}
+
+// "red:2" -> ("red", 2.0). Sin proporción, se usa una parte.
+fn parse_part(arg: &str) -> Result<(String, f64), String> {
+    match arg.rsplit_once(':') {
+        Some((color, parts)) => {
+            let parts = parts
+                .parse()
+                .map_err(|_| format!("invalid proportion in '{}'", arg))?;
+            Ok((color.to_string(), parts))
+        }
+        None => Ok((arg.to_string(), 1.0)),
+    }
+}
+
+fn parse_color(arg: &str) -> Result<Color, Box<dyn Error>> {
+    if arg.starts_with('#') {
+        Ok(Color::from_hex(arg)?)
+    } else {
+        Ok(Color::from(arg.parse::<Mixture>()?))
+    }
+}
+
+fn run(command: Command, truecolor: bool) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Mix(parts) => {
+            // Dos colores con nombre a partes iguales se mezclan en la rueda de color.
+            let named: Vec<Option<Mixture>> = parts
+                .iter()
+                .map(|(color, p)| if *p == 1.0 { color.parse().ok() } else { None })
+                .collect();
+
+            let wheel_mix = match named.as_slice() {
+                [Some(Mixture::Primary(c1)), Some(Mixture::Primary(c2))] => Some(mix(*c1, *c2)),
+                [Some(Mixture::Primary(p)), Some(Mixture::Secondary(s))]
+                | [Some(Mixture::Secondary(s)), Some(Mixture::Primary(p))] => {
+                    Some(mix_with_secondary(*p, *s))
+                }
+                _ => None,
+            };
+
+            match wheel_mix {
+                Some(mixture) => {
+                    println!("{}", swatch(mixture.into(), &mixture.to_string(), truecolor))
+                }
+                None => {
+                    let mut pigments = Vec::new();
+                    for (color, proportion) in &parts {
+                        pigments.push((parse_color(color)?, *proportion));
+                    }
+                    let color = mix_pigments(pigments)?;
+                    let label = format!("~ {}", color.nearest_named());
+                    println!("{}", swatch(color, &label, truecolor));
+                }
+            }
+        }
+        Command::Convert { color, to } => {
+            let color = parse_color(&color)?;
+            let hsl = color.to_hsl();
+            let formats = [
+                ("rgb", format!("rgb({}, {}, {})", color.r, color.g, color.b)),
+                (
+                    "hsl",
+                    format!(
+                        "hsl({:.0}, {:.0}%, {:.0}%)",
+                        hsl.h,
+                        hsl.s * 100.0,
+                        hsl.l * 100.0
+                    ),
+                ),
+                ("hex", color.to_hex()),
+                ("name", color.nearest_named().to_string()),
+            ];
+
+            for (name, value) in formats {
+                match &to {
+                    Some(to) if to == name => println!("{}", value),
+                    Some(_) => {}
+                    None => println!("{:>4}: {}", name, value),
+                }
+            }
+        }
+        Command::Palette { base, scheme, format } => {
+            let palette = Palette::new(base, scheme)
+                .ok_or_else(|| format!("{} has no hue to build a palette from", base))?;
+
+            match format.as_deref() {
+                Some("css") => print!("{}", palette.to_css()),
+                Some("gpl") => print!("{}", palette.to_gpl()),
+                Some("json") => println!("{}", palette.to_json()),
+                _ => {
+                    println!("{}", palette.name());
+                    for &color in palette.colors() {
+                        println!("{}", swatch(color.into(), &color.to_string(), truecolor));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/* Muestra de color: con truecolor se pinta el fondo con el color exacto
+ * mediante la secuencia de escape ESC[48;2;r;g;bm. Si no, solo el texto, en
+ * el mismo orden.
+ */
+fn swatch(color: Color, label: &str, truecolor: bool) -> String {
+    if truecolor {
+        format!(
+            "\x1b[48;2;{};{};{}m      \x1b[0m {} {}",
+            color.r, color.g, color.b, color, label
+        )
+    } else {
+        format!("{} {}", color, label)
+    }
+}
+
+// La mayoría de terminales con truecolor lo anuncian en la variable COLORTERM.
+fn supports_truecolor() -> bool {
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    io::stdout().is_terminal() && (colorterm == "truecolor" || colorterm == "24bit")
+}
 
 fn main() {
-    let red = PrimaryColor::Red;
-    let yellow = PrimaryColor::Yellow;
-    mix(red, yellow);
-}
\ No newline at end of file
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = Command::new(&args).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}\n\n{}", err, USAGE);
+        process::exit(1);
+    });
+
+    if let Err(e) = run(command, supports_truecolor()) {
+        eprintln!("Application error: {}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use art::kinds::{PrimaryColor, SecondaryColor};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn valid_commands() {
+        assert_eq!(
+            Ok(Command::Mix(vec![
+                ("red".to_string(), 2.0),
+                ("#00ff00".to_string(), 1.0),
+                ("blue".to_string(), 0.5),
+            ])),
+            Command::new(&args(&["mix", "red:2", "#00ff00", "blue:0.5"]))
+        );
+        assert_eq!(
+            Ok(Command::Convert {
+                color: "orange".to_string(),
+                to: Some("hex".to_string()),
+            }),
+            Command::new(&args(&["convert", "orange", "--to", "hex"]))
+        );
+        assert_eq!(
+            Ok(Command::Palette {
+                base: Mixture::Secondary(SecondaryColor::Green),
+                scheme: Scheme::Complementary,
+                format: None,
+            }),
+            Command::new(&args(&["palette", "green"]))
+        );
+        assert_eq!(
+            Ok(Command::Palette {
+                base: Mixture::Primary(PrimaryColor::Red),
+                scheme: Scheme::Triadic,
+                format: Some("gpl".to_string()),
+            }),
+            Command::new(&args(&["palette", "red", "--format", "gpl", "--scheme", "triadic"]))
+        );
+    }
+
+    #[test]
+    fn unknown_commands_and_options() {
+        assert_eq!(Err("missing command".to_string()), Command::new(&[]));
+        assert_eq!(
+            Err("unknown command 'paint'".to_string()),
+            Command::new(&args(&["paint", "red"]))
+        );
+        assert_eq!(
+            Err("unexpected argument '--from'".to_string()),
+            Command::new(&args(&["convert", "red", "--from", "hex"]))
+        );
+        assert_eq!(
+            Err("missing value for --to".to_string()),
+            Command::new(&args(&["convert", "red", "--to"]))
+        );
+        assert_eq!(
+            Err("unknown format 'cmyk'".to_string()),
+            Command::new(&args(&["convert", "red", "--to", "cmyk"]))
+        );
+        assert_eq!(
+            Err("unknown format 'svg'".to_string()),
+            Command::new(&args(&["palette", "red", "--format", "svg"]))
+        );
+        assert!(Command::new(&args(&["palette", "red", "--scheme", "rainbow"])).is_err());
+        assert_eq!(
+            Err("mix needs at least two colors".to_string()),
+            Command::new(&args(&["mix", "red"]))
+        );
+    }
+
+    #[test]
+    fn malformed_parts() {
+        assert_eq!(Ok(("red".to_string(), 1.0)), parse_part("red"));
+        assert_eq!(Ok(("red".to_string(), 0.25)), parse_part("red:0.25"));
+        assert_eq!(
+            Err("invalid proportion in 'red:lots'".to_string()),
+            parse_part("red:lots")
+        );
+        assert_eq!(
+            Err("invalid proportion in 'red:'".to_string()),
+            parse_part("red:")
+        );
+        assert_eq!(
+            Err("invalid proportion in 'blue:2:'".to_string()),
+            Command::new(&args(&["mix", "red", "blue:2:"]))
+        );
+    }
+
+    #[test]
+    fn swatches_keep_the_same_order() {
+        let color = Color::from_hex("#ff8800").unwrap();
+        let plain = swatch(color, "orange", false);
+        let painted = swatch(color, "orange", true);
+
+        assert_eq!(format!("{} orange", color), plain);
+        assert!(painted.ends_with(&plain));
+    }
+}
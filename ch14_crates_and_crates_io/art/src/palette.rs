@@ -0,0 +1,257 @@
+//! Palettes built from a base color with the classic color harmony rules.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::kinds::Mixture;
+
+/// A color harmony rule, defined by positions on the twelve-step color wheel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// The base color and the one opposite to it.
+    Complementary,
+    /// The base color and its two neighbours.
+    Analogous,
+    /// Three colors evenly spaced around the wheel.
+    Triadic,
+    /// The base color and the two neighbours of its complement.
+    SplitComplementary,
+}
+
+impl Scheme {
+    /// Every scheme.
+    pub const ALL: [Scheme; 4] = [
+        Scheme::Complementary,
+        Scheme::Analogous,
+        Scheme::Triadic,
+        Scheme::SplitComplementary,
+    ];
+
+    // Pasos en la rueda (de 12 colores) desde el color base, en sentido horario.
+    fn steps(self) -> &'static [usize] {
+        match self {
+            Scheme::Complementary => &[0, 6],
+            Scheme::Analogous => &[0, 11, 1],
+            Scheme::Triadic => &[0, 4, 8],
+            Scheme::SplitComplementary => &[0, 5, 7],
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Scheme::Complementary => "complementary",
+            Scheme::Analogous => "analogous",
+            Scheme::Triadic => "triadic",
+            Scheme::SplitComplementary => "split-complementary",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The error returned when parsing an unknown scheme name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSchemeError(String);
+
+impl fmt::Display for ParseSchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown palette scheme '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseSchemeError {}
+
+impl FromStr for Scheme {
+    type Err = ParseSchemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scheme::ALL
+            .into_iter()
+            .find(|scheme| scheme.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseSchemeError(s.to_string()))
+    }
+}
+
+/// A set of named colors that go well together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    base: Mixture,
+    scheme: Scheme,
+    colors: Vec<Mixture>,
+}
+
+impl Palette {
+    /// Builds the palette for `base` following `scheme`, starting with
+    /// `base` itself. Returns `None` for `Mixture::Neutral`, which has no
+    /// hue to build a harmony from.
+    ///
+    /// # Examples
+    /// ```
+    /// use art::palette::{Palette, Scheme};
+    /// use art::{Mixture, PrimaryColor};
+    ///
+    /// let red = Mixture::Primary(PrimaryColor::Red);
+    /// let triadic = Palette::new(red, Scheme::Triadic).unwrap();
+    /// let names: Vec<String> = triadic.colors().iter().map(|c| c.to_string()).collect();
+    ///
+    /// assert_eq!(vec!["red", "yellow", "blue"], names);
+    /// ```
+    pub fn new(base: Mixture, scheme: Scheme) -> Option<Palette> {
+        let position = base.wheel_position()?;
+        let colors = scheme
+            .steps()
+            .iter()
+            .map(|step| Mixture::from_wheel_position(position + step))
+            .collect();
+
+        Some(Palette {
+            base,
+            scheme,
+            colors,
+        })
+    }
+
+    pub fn base(&self) -> Mixture {
+        self.base
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    pub fn colors(&self) -> &[Mixture] {
+        &self.colors
+    }
+
+    /// Name of the palette, like `"triadic red"`.
+    pub fn name(&self) -> String {
+        format!("{} {}", self.scheme, self.base)
+    }
+
+    /// Exports the palette as CSS custom properties on `:root`.
+    pub fn to_css(&self) -> String {
+        let mut css = String::from(":root {\n");
+        for &color in &self.colors {
+            css += &format!("  --{}: {};\n", color, Color::from(color));
+        }
+        css += "}\n";
+        css
+    }
+
+    /// Exports the palette in the GIMP `.gpl` palette format.
+    pub fn to_gpl(&self) -> String {
+        let mut gpl = format!(
+            "GIMP Palette\nName: {}\nColumns: {}\n#\n",
+            self.name(),
+            self.colors.len()
+        );
+        for &color in &self.colors {
+            let Color { r, g, b } = Color::from(color);
+            gpl += &format!("{:3} {:3} {:3}\t{}\n", r, g, b, color);
+        }
+        gpl
+    }
+
+    /// Exports the palette as a JSON object.
+    pub fn to_json(&self) -> String {
+        // Los nombres son ASCII sin comillas, así que no hace falta escaparlos.
+        let colors: Vec<String> = self
+            .colors
+            .iter()
+            .map(|&color| {
+                format!(
+                    "{{\"name\": \"{}\", \"hex\": \"{}\"}}",
+                    color,
+                    Color::from(color)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"name\": \"{}\", \"base\": \"{}\", \"scheme\": \"{}\", \"colors\": [{}]}}",
+            self.name(),
+            self.base,
+            self.scheme,
+            colors.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinds::*;
+
+    fn names(palette: &Palette) -> Vec<String> {
+        palette.colors().iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn schemes_from_red() {
+        let red = Mixture::Primary(PrimaryColor::Red);
+        let cases = [
+            (Scheme::Complementary, vec!["red", "green"]),
+            (Scheme::Analogous, vec!["red", "red-purple", "red-orange"]),
+            (Scheme::Triadic, vec!["red", "yellow", "blue"]),
+            (Scheme::SplitComplementary, vec!["red", "yellow-green", "blue-green"]),
+        ];
+
+        for (scheme, expected) in cases {
+            assert_eq!(expected, names(&Palette::new(red, scheme).unwrap()));
+        }
+    }
+
+    #[test]
+    fn schemes_wrap_around_the_wheel() {
+        let red_purple = Mixture::Tertiary(TertiaryColor::RedPurple);
+        let palette = Palette::new(red_purple, Scheme::Analogous).unwrap();
+        assert_eq!(vec!["red-purple", "purple", "red"], names(&palette));
+    }
+
+    #[test]
+    fn complementary_is_symmetric() {
+        for base in Mixture::ALL.into_iter().filter(|&c| c != Mixture::Neutral) {
+            let complement = Palette::new(base, Scheme::Complementary).unwrap().colors()[1];
+            let back = Palette::new(complement, Scheme::Complementary).unwrap().colors()[1];
+            assert_eq!(base, back);
+        }
+    }
+
+    #[test]
+    fn neutral_has_no_palette() {
+        for scheme in Scheme::ALL {
+            assert_eq!(None, Palette::new(Mixture::Neutral, scheme));
+        }
+    }
+
+    #[test]
+    fn parse_scheme() {
+        for scheme in Scheme::ALL {
+            assert_eq!(Ok(scheme), scheme.to_string().parse());
+        }
+        assert_eq!(Ok(Scheme::Triadic), "Triadic".parse());
+        assert!("square".parse::<Scheme>().is_err());
+    }
+
+    #[test]
+    fn export_formats() {
+        let blue = Mixture::Primary(PrimaryColor::Blue);
+        let palette = Palette::new(blue, Scheme::Complementary).unwrap();
+
+        assert_eq!(
+            ":root {\n  --blue: #0247fe;\n  --orange: #fb9902;\n}\n",
+            palette.to_css()
+        );
+        assert_eq!(
+            "GIMP Palette\nName: complementary blue\nColumns: 2\n#\n  2  71 254\tblue\n251 153   2\torange\n",
+            palette.to_gpl()
+        );
+        assert_eq!(
+            "{\"name\": \"complementary blue\", \"base\": \"blue\", \"scheme\": \"complementary\", \
+             \"colors\": [{\"name\": \"blue\", \"hex\": \"#0247fe\"}, {\"name\": \"orange\", \"hex\": \"#fb9902\"}]}",
+            palette.to_json()
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Weighted mixing of pigments with a subtractive color model.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::color::Color;
+
+/* Modelo sustractivo: cada canal RGB se trata como la reflectancia del pigmento,
+ * es decir, la fracción de esa luz que no absorbe. Al mezclar pigmentos se
+ * multiplican las reflectancias, ponderadas por la proporción de cada uno
+ * (media geométrica ponderada). Por eso mezclar rojo y azul oscurece en vez de
+ * dar el promedio de ambos, como pasa con la pintura real.
+ *
+ * Las reflectancias se calculan en luz lineal, no en sRGB.
+ */
+
+// Reflectancia mínima, para que ln(0) no dé -inf.
+const MIN_REFLECTANCE: f64 = 1e-6;
+
+/// The error returned when a set of pigments can't be mixed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MixError {
+    /// There are no pigments, or all of them have a proportion of zero.
+    Empty,
+    /// A proportion is negative, infinite or NaN.
+    InvalidProportion(f64),
+}
+
+impl fmt::Display for MixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MixError::Empty => write!(f, "there are no pigments to mix"),
+            MixError::InvalidProportion(p) => write!(f, "invalid proportion {}", p),
+        }
+    }
+}
+
+impl Error for MixError {}
+
+/// Mixes any number of pigments, each given with its proportion.
+///
+/// Proportions are relative: `2.0` parts of red and `1.0` part of yellow
+/// give the same result as `0.5` and `0.25`. The order of the pigments
+/// doesn't matter.
+///
+/// # Examples
+/// ```
+/// use art::{mix_pigments, Color, PrimaryColor};
+///
+/// let orange = mix_pigments([(PrimaryColor::Red, 2.0), (PrimaryColor::Yellow, 1.0)]).unwrap();
+/// let same = mix_pigments([(PrimaryColor::Yellow, 0.5), (PrimaryColor::Red, 1.0)]).unwrap();
+///
+/// assert_eq!(orange, same);
+/// assert_eq!(Color::rgb(254, 77, 27), orange);
+/// ```
+pub fn mix_pigments<I, C>(parts: I) -> Result<Color, MixError>
+where
+    I: IntoIterator<Item = (C, f64)>,
+    C: Into<Color>,
+{
+    let mut pigments: Vec<(Color, f64)> = Vec::new();
+    for (color, proportion) in parts {
+        if !proportion.is_finite() || proportion < 0.0 {
+            return Err(MixError::InvalidProportion(proportion));
+        }
+        pigments.push((color.into(), proportion));
+    }
+
+    let total: f64 = pigments.iter().map(|(_, proportion)| proportion).sum();
+    if total == 0.0 {
+        return Err(MixError::Empty);
+    }
+
+    // Se ordenan los pigmentos para que las sumas en coma flotante den
+    // exactamente el mismo resultado sin importar el orden de entrada.
+    pigments.sort_by(|(c1, p1), (c2, p2)| {
+        (c1.r, c1.g, c1.b)
+            .cmp(&(c2.r, c2.g, c2.b))
+            .then(p1.total_cmp(p2))
+    });
+
+    let channel = |get: fn(&Color) -> u8| {
+        let log_sum: f64 = pigments
+            .iter()
+            .map(|(color, proportion)| {
+                let reflectance = to_linear(get(color)).max(MIN_REFLECTANCE);
+                proportion / total * reflectance.ln()
+            })
+            .sum();
+        from_linear(log_sum.exp())
+    };
+
+    Ok(Color::rgb(
+        channel(|c| c.r),
+        channel(|c| c.g),
+        channel(|c| c.b),
+    ))
+}
+
+// Conversión de un canal sRGB (0-255) a luz lineal (0.0-1.0).
+fn to_linear(channel: u8) -> f64 {
+    let c = channel as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Conversión inversa, de luz lineal a un canal sRGB.
+fn from_linear(linear: f64) -> u8 {
+    let c = if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kinds::*;
+    use proptest::prelude::*;
+
+    fn any_color() -> impl Strategy<Value = Color> {
+        any::<(u8, u8, u8)>().prop_map(Color::from)
+    }
+
+    #[test]
+    fn invalid_mixes() {
+        let none: [(Color, f64); 0] = [];
+        assert_eq!(Err(MixError::Empty), mix_pigments(none));
+        assert_eq!(Err(MixError::Empty), mix_pigments([(PrimaryColor::Red, 0.0)]));
+        assert_eq!(
+            Err(MixError::InvalidProportion(-1.0)),
+            mix_pigments([(PrimaryColor::Red, 1.0), (PrimaryColor::Blue, -1.0)])
+        );
+        assert!(mix_pigments([(PrimaryColor::Red, f64::NAN)]).is_err());
+        assert!(mix_pigments([(PrimaryColor::Red, f64::INFINITY)]).is_err());
+    }
+
+    #[test]
+    fn mixing_is_subtractive() {
+        let purple = mix_pigments([(PrimaryColor::Red, 1.0), (PrimaryColor::Blue, 1.0)]).unwrap();
+        let red = Color::from(PrimaryColor::Red);
+        let blue = Color::from(PrimaryColor::Blue);
+
+        // Una mezcla de pigmentos nunca refleja más luz que sus componentes.
+        assert!(purple.r <= red.r.max(blue.r));
+        assert!(purple.g <= red.g.max(blue.g));
+        assert!(purple.b <= red.b.max(blue.b));
+        assert_eq!(Color::rgb(43, 53, 79), purple);
+    }
+
+    #[test]
+    fn mixed_hue_lies_between_its_pigments() {
+        let cases = [
+            (PrimaryColor::Red, PrimaryColor::Yellow),
+            (PrimaryColor::Yellow, PrimaryColor::Blue),
+        ];
+
+        for (c1, c2) in cases {
+            let mixed = mix_pigments([(c1, 1.0), (c2, 1.0)]).unwrap();
+            let hue = mixed.to_hsl().h;
+            let h1 = Color::from(c1).to_hsl().h;
+            let h2 = Color::from(c2).to_hsl().h;
+            assert!(h1 < hue && hue < h2, "{} + {} = {}", c1, c2, mixed);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn identity(color in any_color(), proportion in 0.01f64..100.0) {
+            prop_assert_eq!(Ok(color), mix_pigments([(color, proportion)]));
+            prop_assert_eq!(Ok(color), mix_pigments([(color, proportion), (color, 1.0)]));
+        }
+
+        #[test]
+        fn zero_proportion_has_no_effect(c1 in any_color(), c2 in any_color()) {
+            prop_assert_eq!(Ok(c1), mix_pigments([(c1, 1.0), (c2, 0.0)]));
+        }
+
+        #[test]
+        fn commutative(
+            parts in prop::collection::vec((any_color(), 0.01f64..100.0), 1..6),
+        ) {
+            let mut reversed = parts.clone();
+            reversed.reverse();
+            prop_assert_eq!(mix_pigments(parts), mix_pigments(reversed));
+        }
+
+        #[test]
+        fn scale_invariant(
+            c1 in any_color(),
+            c2 in any_color(),
+            p1 in 1u32..10,
+            p2 in 1u32..10,
+        ) {
+            let parts = mix_pigments([(c1, p1 as f64), (c2, p2 as f64)]);
+            let doubled = mix_pigments([(c1, 2.0 * p1 as f64), (c2, 2.0 * p2 as f64)]);
+            prop_assert_eq!(parts, doubled);
+        }
+    }
+}
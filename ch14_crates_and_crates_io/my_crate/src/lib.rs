@@ -23,6 +23,8 @@
 //! `my_Crate` is a collection of utilities to make performing certain
 //! calculations more convenient
 
+pub mod stats;
+
 // Comentario de documentación (///)
 // Con "cargo doc --open" genera un HTML y lo abre en el navegador. 
 // Con "cargo test" se puede probar el test de la documentación
@@ -40,3 +42,210 @@ pub fn add_one(x: i32) -> i32 {
     x + 1
 }
 
+
+/// Greatest common divisor of two numbers, using Euclid's algorithm.
+///
+/// `gcd(0, 0)` is `0`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(6, my_crate::gcd(48, 18));
+/// assert_eq!(7, my_crate::gcd(0, 7));
+/// assert_eq!(1, my_crate::gcd(17, 5));
+/// ```
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple of two numbers, or `None` if it doesn't fit in a `u64`.
+///
+/// `lcm(0, x)` is `0`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(Some(36), my_crate::lcm(12, 18));
+/// assert_eq!(Some(0), my_crate::lcm(0, 5));
+/// assert_eq!(None, my_crate::lcm(u64::MAX, u64::MAX - 1));
+/// ```
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    // Se divide antes de multiplicar para no desbordar sin necesidad.
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Integer square root: the largest number whose square is at most `n`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(4, my_crate::isqrt(24));
+/// assert_eq!(5, my_crate::isqrt(25));
+/// assert_eq!(0, my_crate::isqrt(0));
+/// assert_eq!(u32::MAX as u64, my_crate::isqrt(u64::MAX));
+/// ```
+pub fn isqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+
+    // Método de Newton: empezando por encima de la raíz, cada paso se acerca
+    // hasta que deja de bajar.
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Computes `base` raised to `exp`, modulo `modulus`, without overflowing.
+///
+/// # Panics
+///
+/// Panics if `modulus` is `0`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(445, my_crate::mod_pow(4, 13, 497));
+/// assert_eq!(1, my_crate::mod_pow(2, 0, 7));
+/// assert_eq!(0, my_crate::mod_pow(5, 3, 1));
+/// ```
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+    assert!(modulus != 0, "modulus must not be zero");
+
+    // Exponenciación binaria. Los productos se hacen en u128 para no desbordar.
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    result as u64
+}
+
+/// Checks whether `n` is a prime number.
+///
+/// Uses the Miller-Rabin test with a fixed set of bases that makes it
+/// exact (not probabilistic) for every `u64`.
+///
+/// # Examples
+/// ```
+/// assert!(my_crate::is_prime(2));
+/// assert!(my_crate::is_prime(97));
+/// assert!(my_crate::is_prime(18_446_744_073_709_551_557));
+///
+/// assert!(!my_crate::is_prime(1));
+/// assert!(!my_crate::is_prime(561)); // Número de Carmichael
+/// assert!(!my_crate::is_prime(3_215_031_751));
+/// ```
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    // n - 1 = d * 2^s, con d impar
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    BASES.iter().all(|&a| {
+        let mut x = mod_pow(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mod_mul(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// Splits `n` into its prime factors, in ascending order and repeated as
+/// many times as they divide `n`. `0` and `1` have no prime factors.
+///
+/// # Examples
+/// ```
+/// assert_eq!(vec![2, 2, 3, 5], my_crate::factorize(60));
+/// assert_eq!(vec![97], my_crate::factorize(97));
+/// assert!(my_crate::factorize(1).is_empty());
+///
+/// // Producto de dos primos grandes
+/// assert_eq!(vec![4_294_967_279, 4_294_967_291], my_crate::factorize(18_446_743_979_220_271_189));
+/// ```
+pub fn factorize(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    if n < 2 {
+        return factors;
+    }
+
+    // Primero se quitan los factores pequeños por división.
+    for p in [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while n.is_multiple_of(p) {
+            factors.push(p);
+            n /= p;
+        }
+    }
+
+    // Lo que queda se parte con el algoritmo rho de Pollard.
+    let mut pending = vec![n];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            factors.push(m);
+            continue;
+        }
+        let divisor = pollard_rho(m);
+        pending.push(divisor);
+        pending.push(m / divisor);
+    }
+
+    factors.sort_unstable();
+    factors
+}
+
+fn mod_mul(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+// Encuentra un divisor no trivial de un número compuesto e impar.
+fn pollard_rho(n: u64) -> u64 {
+    let mut c = 1;
+    loop {
+        let f = |x: u64| (mod_mul(x, x, n) + c) % n;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+
+        // Si d == n el ciclo no ha servido: se prueba con otra constante.
+        if d != n {
+            return d;
+        }
+        c += 1;
+    }
+}
@@ -0,0 +1,450 @@
+//! Descriptive statistics over any iterator of `f64`.
+
+use std::collections::VecDeque;
+
+/// Mean and variance computed one value at a time, without storing the data.
+///
+/// Uses Welford's algorithm, which stays accurate even when the values are
+/// large and close to each other, unlike summing squares.
+///
+/// # Examples
+/// ```
+/// use my_crate::stats::RunningStats;
+///
+/// let stats: RunningStats = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0].into_iter().collect();
+///
+/// assert_eq!(8, stats.count());
+/// assert_eq!(Some(5.0), stats.mean());
+/// assert_eq!(Some(4.0), stats.variance());
+/// assert_eq!(Some(2.0), stats.std_dev());
+/// assert_eq!(Some(9.0), stats.max());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RunningStats {
+    count: u64,
+    mean: f64,
+    // Suma de los cuadrados de las diferencias con la media.
+    m2: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl RunningStats {
+    pub fn new() -> RunningStats {
+        RunningStats::default()
+    }
+
+    /// Adds a value.
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+
+        self.min = Some(self.min.map_or(x, |min| min.min(x)));
+        self.max = Some(self.max.map_or(x, |max| max.max(x)));
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean, or `None` if there are no values.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// The population variance, or `None` if there are no values.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// The sample variance (divided by `n - 1`), or `None` with fewer than
+    /// two values.
+    pub fn sample_variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+
+    /// The population standard deviation.
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// The sample standard deviation.
+    pub fn sample_std_dev(&self) -> Option<f64> {
+        self.sample_variance().map(f64::sqrt)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+}
+
+impl Extend<f64> for RunningStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl FromIterator<f64> for RunningStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = RunningStats::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+/// The mean of the values, or `None` if there are none.
+///
+/// # Examples
+/// ```
+/// assert_eq!(Some(2.5), my_crate::stats::mean([1.0, 2.0, 3.0, 4.0]));
+/// assert_eq!(None, my_crate::stats::mean(Vec::new()));
+/// ```
+pub fn mean<I: IntoIterator<Item = f64>>(data: I) -> Option<f64> {
+    data.into_iter().collect::<RunningStats>().mean()
+}
+
+/// The median of the values, or `None` if there are none. With an even
+/// number of values, it's the mean of the two in the middle.
+///
+/// # Examples
+/// ```
+/// assert_eq!(Some(3.0), my_crate::stats::median([5.0, 1.0, 3.0]));
+/// assert_eq!(Some(2.5), my_crate::stats::median([4.0, 1.0, 3.0, 2.0]));
+/// ```
+pub fn median<I: IntoIterator<Item = f64>>(data: I) -> Option<f64> {
+    percentile(data, 50.0)
+}
+
+/// The `p`-th percentile of the values, with `p` from `0.0` to `100.0`.
+///
+/// Interpolates linearly between the closest values, like the default of
+/// spreadsheets and NumPy. Returns `None` if there are no values or `p` is
+/// out of range.
+///
+/// # Examples
+/// ```
+/// let data = [15.0, 20.0, 35.0, 40.0, 50.0];
+///
+/// assert_eq!(Some(15.0), my_crate::stats::percentile(data, 0.0));
+/// assert_eq!(Some(29.0), my_crate::stats::percentile(data, 40.0));
+/// assert_eq!(Some(50.0), my_crate::stats::percentile(data, 100.0));
+/// assert_eq!(None, my_crate::stats::percentile(data, 101.0));
+/// ```
+pub fn percentile<I: IntoIterator<Item = f64>>(data: I, p: f64) -> Option<f64> {
+    if !(0.0..=100.0).contains(&p) {
+        return None;
+    }
+
+    let mut sorted: Vec<f64> = data.into_iter().collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let fraction = rank - lower as f64;
+
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * fraction)
+}
+
+/// Counts of values in equal-width buckets between `min` and `max`.
+///
+/// # Examples
+/// ```
+/// use my_crate::stats::Histogram;
+///
+/// let mut histogram = Histogram::new(0.0, 10.0, 5);
+/// histogram.extend([0.5, 1.0, 2.5, 9.9, 10.0, 12.0]);
+///
+/// assert_eq!(&[2, 1, 0, 0, 2], histogram.counts());
+/// assert_eq!((2.0, 4.0), histogram.bucket_range(1));
+/// assert_eq!(1, histogram.outside());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    min: f64,
+    max: f64,
+    counts: Vec<u64>,
+    outside: u64,
+}
+
+impl Histogram {
+    /// Creates an empty histogram. The last bucket includes `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buckets` is `0` or `min` isn't smaller than `max`.
+    pub fn new(min: f64, max: f64, buckets: usize) -> Histogram {
+        assert!(buckets > 0, "a histogram needs at least one bucket");
+        assert!(min < max, "min must be smaller than max");
+
+        Histogram {
+            min,
+            max,
+            counts: vec![0; buckets],
+            outside: 0,
+        }
+    }
+
+    /// Adds a value to its bucket, or to the `outside` count if it's out of
+    /// range or NaN.
+    pub fn add(&mut self, x: f64) {
+        if !(self.min..=self.max).contains(&x) {
+            self.outside += 1;
+            return;
+        }
+
+        let buckets = self.counts.len();
+        let index = ((x - self.min) / self.width()) as usize;
+        self.counts[index.min(buckets - 1)] += 1;
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Number of values that didn't fall in any bucket.
+    pub fn outside(&self) -> u64 {
+        self.outside
+    }
+
+    /// Lower and upper bounds of the bucket at `index`.
+    pub fn bucket_range(&self, index: usize) -> (f64, f64) {
+        let start = self.min + self.width() * index as f64;
+        (start, start + self.width())
+    }
+
+    fn width(&self) -> f64 {
+        (self.max - self.min) / self.counts.len() as f64
+    }
+}
+
+impl Extend<f64> for Histogram {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        for x in iter {
+            self.add(x);
+        }
+    }
+}
+
+/// Builds a histogram whose range goes from the smallest to the largest
+/// value, leaving out NaN and infinite values. Returns `None` if there are no
+/// finite values, `buckets` is `0` or the range is too wide to split.
+///
+/// # Examples
+/// ```
+/// let histogram = my_crate::stats::histogram([1.0, 2.0, 2.0, 3.0, 5.0], 2).unwrap();
+///
+/// assert_eq!(&[3, 2], histogram.counts());
+/// assert_eq!((1.0, 3.0), histogram.bucket_range(0));
+/// ```
+pub fn histogram<I: IntoIterator<Item = f64>>(data: I, buckets: usize) -> Option<Histogram> {
+    let data: Vec<f64> = data.into_iter().filter(|x| x.is_finite()).collect();
+    let stats: RunningStats = data.iter().copied().collect();
+    let (min, mut max) = (stats.min()?, stats.max()?);
+
+    if buckets == 0 {
+        return None;
+    }
+    // Si todos los valores son iguales, el rango no puede tener ancho cero.
+    // Sumar 1.0 no cambia un número muy grande, así que se suma su magnitud.
+    if min == max {
+        max = min + min.abs().max(1.0);
+    }
+    if !(max - min).is_finite() {
+        return None;
+    }
+
+    let mut histogram = Histogram::new(min, max, buckets);
+    histogram.extend(data);
+    Some(histogram)
+}
+
+/// Iterator returned by [`moving_average`].
+pub struct MovingAverage<I> {
+    iter: I,
+    window: VecDeque<f64>,
+    size: usize,
+}
+
+impl<I: Iterator<Item = f64>> Iterator for MovingAverage<I> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+
+        // Se suma la ventana entera en vez de llevar una suma acumulada,
+        // que iría perdiendo precisión al restar los valores que salen.
+        let average = self.window.iter().sum::<f64>() / self.size as f64;
+        self.window.pop_front();
+        Some(average)
+    }
+}
+
+/// Simple moving average: the mean of every window of `size` consecutive
+/// values.
+///
+/// # Panics
+///
+/// Panics if `size` is `0`.
+///
+/// # Examples
+/// ```
+/// let data = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let averages: Vec<f64> = my_crate::stats::moving_average(data, 3).collect();
+///
+/// assert_eq!(vec![2.0, 3.0, 4.0], averages);
+/// ```
+pub fn moving_average<I: IntoIterator<Item = f64>>(
+    data: I,
+    size: usize,
+) -> MovingAverage<I::IntoIter> {
+    assert!(size > 0, "the window must have at least one value");
+
+    MovingAverage {
+        iter: data.into_iter(),
+        window: VecDeque::with_capacity(size),
+        size,
+    }
+}
+
+/// Exponential moving average: each value is weighted by `alpha` and the
+/// previous average by `1 - alpha`, starting with the first value.
+///
+/// # Panics
+///
+/// Panics if `alpha` isn't between `0.0` (excluded) and `1.0`.
+///
+/// # Examples
+/// ```
+/// let averages: Vec<f64> =
+///     my_crate::stats::exponential_moving_average([10.0, 20.0, 20.0], 0.5).collect();
+///
+/// assert_eq!(vec![10.0, 15.0, 17.5], averages);
+/// ```
+pub fn exponential_moving_average<I: IntoIterator<Item = f64>>(
+    data: I,
+    alpha: f64,
+) -> impl Iterator<Item = f64> {
+    assert!(alpha > 0.0 && alpha <= 1.0, "alpha must be in (0, 1]");
+
+    data.into_iter().scan(None, move |average: &mut Option<f64>, x| {
+        let next = match *average {
+            Some(previous) => alpha * x + (1.0 - alpha) * previous,
+            None => x,
+        };
+        *average = Some(next);
+        Some(next)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        let tolerance = 1e-9 * expected.abs().max(1.0);
+        assert!((expected - actual).abs() < tolerance, "expected {}, got {}", expected, actual);
+    }
+
+    /* Conjuntos de datos de referencia del NIST (Statistical Reference Datasets).
+     * NumAcc1 y NumAcc4 tienen valores grandes muy parecidos entre sí, que
+     * hacen fallar a los algoritmos ingenuos de varianza.
+     * https://www.itl.nist.gov/div898/strd/univ/homepage.html
+     */
+    #[test]
+    fn nist_numacc1() {
+        let stats: RunningStats = [10000001.0, 10000003.0, 10000002.0].into_iter().collect();
+        assert_close(10000002.0, stats.mean().unwrap());
+        assert_close(1.0, stats.sample_std_dev().unwrap());
+    }
+
+    #[test]
+    fn nist_numacc4() {
+        let mut data = vec![1000000000.2];
+        for _ in 0..500 {
+            data.push(1000000000.1);
+            data.push(1000000000.3);
+        }
+
+        let stats: RunningStats = data.iter().copied().collect();
+        assert_eq!(1001, stats.count());
+        assert_close(1000000000.2, stats.mean().unwrap());
+        assert!((0.1 - stats.sample_std_dev().unwrap()).abs() < 1e-7);
+        assert_close(1000000000.2, median(data).unwrap());
+    }
+
+    #[test]
+    fn mean_matches_the_direct_sum() {
+        // Unos pocos valores del comienzo de NIST Lew, no el conjunto entero.
+        let data = [-213.0, -564.0, -35.0, -15.0, 141.0, 115.0, -420.0, -360.0, 203.0, -338.0];
+        let direct = data.iter().sum::<f64>() / data.len() as f64;
+        assert_close(direct, mean(data).unwrap());
+    }
+
+    #[test]
+    fn empty_data() {
+        let stats = RunningStats::new();
+        assert_eq!(None, stats.mean());
+        assert_eq!(None, stats.variance());
+        assert_eq!(None, median(Vec::new()));
+        assert!(histogram(Vec::new(), 3).is_none());
+        assert_eq!(0, moving_average(Vec::new(), 2).count());
+    }
+
+    #[test]
+    fn single_value() {
+        let stats: RunningStats = [4.0].into_iter().collect();
+        assert_eq!(Some(0.0), stats.variance());
+        assert_eq!(None, stats.sample_variance());
+        assert_eq!(Some(4.0), percentile([4.0], 75.0));
+
+        let histogram = histogram([4.0, 4.0], 2).unwrap();
+        assert_eq!(&[2, 0], histogram.counts());
+    }
+
+    #[test]
+    fn quartiles() {
+        let data: Vec<f64> = (1..=9).map(f64::from).collect();
+        assert_eq!(Some(3.0), percentile(data.clone(), 25.0));
+        assert_eq!(Some(5.0), median(data.clone()));
+        assert_eq!(Some(7.0), percentile(data, 75.0));
+    }
+
+    #[test]
+    fn histogram_ignores_nan() {
+        let histogram = histogram([1.0, f64::NAN, 2.0], 1).unwrap();
+        assert_eq!(&[2], histogram.counts());
+    }
+
+    #[test]
+    fn histogram_of_extreme_values() {
+        // 1e17 + 1.0 sigue siendo 1e17
+        let large = histogram([1e17, 1e17], 2).unwrap();
+        assert_eq!(&[2, 0], large.counts());
+
+        let infinite = histogram([1.0, f64::INFINITY, f64::NEG_INFINITY], 1).unwrap();
+        assert_eq!(&[1], infinite.counts());
+        assert!(histogram([f64::INFINITY], 2).is_none());
+
+        assert!(histogram([f64::MAX, f64::MAX], 2).is_none());
+        assert!(histogram([-f64::MAX, f64::MAX], 2).is_none());
+    }
+
+    #[test]
+    fn window_larger_than_data() {
+        assert_eq!(0, moving_average([1.0, 2.0], 3).count());
+    }
+}
@@ -1,7 +1,109 @@
-use rand;
+use num_bigint::{BigInt, BigUint};
 
-pub fn add_one(x: i32) -> i32 {
-    x + 1
+pub mod random;
+
+/// Adding one to a number, with the same overflow flavours as the standard
+/// integer methods (`checked_add`, `saturating_add` and `wrapping_add`).
+pub trait AddOne: Sized {
+    /// Adds one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows, in both debug and release builds.
+    fn add_one(self) -> Self {
+        self.checked_add_one().expect("attempt to add one with overflow")
+    }
+
+    /// Adds one, returning `None` on overflow.
+    fn checked_add_one(self) -> Option<Self>;
+
+    /// Adds one, staying at the maximum value on overflow.
+    fn saturating_add_one(self) -> Self;
+
+    /// Adds one, wrapping around to the minimum value on overflow.
+    fn wrapping_add_one(self) -> Self;
+
+    /// Adds `n` at once, returning `None` on overflow. The same as calling
+    /// `checked_add_one` `n` times, without the loop.
+    fn checked_add_n(self, n: u32) -> Option<Self>;
+}
+
+macro_rules! impl_add_one {
+    ($($t:ty),*) => {
+        $(
+            impl AddOne for $t {
+                fn checked_add_one(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn saturating_add_one(self) -> Self {
+                    self.saturating_add(1)
+                }
+
+                fn wrapping_add_one(self) -> Self {
+                    self.wrapping_add(1)
+                }
+
+                // n puede no caber en el tipo (u8, i16...), y entonces seguro que desborda
+                fn checked_add_n(self, n: u32) -> Option<Self> {
+                    <$t>::try_from(n).ok().and_then(|n| self.checked_add(n))
+                }
+            }
+        )*
+    };
+}
+
+impl_add_one!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// Los enteros grandes no tienen máximo, así que nunca hay overflow.
+macro_rules! impl_add_one_unbounded {
+    ($($t:ty),*) => {
+        $(
+            impl AddOne for $t {
+                fn checked_add_one(self) -> Option<Self> {
+                    Some(self + 1u8)
+                }
+
+                fn saturating_add_one(self) -> Self {
+                    self + 1u8
+                }
+
+                fn wrapping_add_one(self) -> Self {
+                    self + 1u8
+                }
+
+                fn checked_add_n(self, n: u32) -> Option<Self> {
+                    Some(self + n)
+                }
+            }
+        )*
+    };
+}
+
+impl_add_one_unbounded!(BigInt, BigUint);
+
+/// Adds one to the number given.
+///
+/// # Panics
+///
+/// Panics if the result overflows.
+pub fn add_one<T: AddOne>(x: T) -> T {
+    x.add_one()
+}
+
+/// Adds one to the number given, returning `None` on overflow.
+pub fn checked_add_one<T: AddOne>(x: T) -> Option<T> {
+    x.checked_add_one()
+}
+
+/// Adds one to the number given, staying at the maximum value on overflow.
+pub fn saturating_add_one<T: AddOne>(x: T) -> T {
+    x.saturating_add_one()
+}
+
+/// Adds one to the number given, wrapping around on overflow.
+pub fn wrapping_add_one<T: AddOne>(x: T) -> T {
+    x.wrapping_add_one()
 }
 
 #[cfg(test)]
@@ -12,4 +114,77 @@ mod tests {
     fn it_works() {
         assert_eq!(3, add_one(2));
     }
-}
\ No newline at end of file
+
+    // Genera un módulo de tests por cada tipo con sus valores límite.
+    macro_rules! boundary_tests {
+        ($($t:ident),*) => {
+            $(
+                mod $t {
+                    use super::*;
+
+                    #[test]
+                    fn below_max() {
+                        assert_eq!($t::MAX, add_one($t::MAX - 1));
+                        assert_eq!(Some($t::MAX), checked_add_one($t::MAX - 1));
+                        assert_eq!($t::MAX, saturating_add_one($t::MAX - 1));
+                        assert_eq!($t::MAX, wrapping_add_one($t::MAX - 1));
+                    }
+
+                    #[test]
+                    fn at_max() {
+                        assert_eq!(None, checked_add_one($t::MAX));
+                        assert_eq!($t::MAX, saturating_add_one($t::MAX));
+                        assert_eq!($t::MIN, wrapping_add_one($t::MAX));
+                    }
+
+                    #[test]
+                    #[should_panic(expected = "attempt to add one with overflow")]
+                    fn add_one_at_max_panics() {
+                        add_one($t::MAX);
+                    }
+
+                    #[test]
+                    fn at_min_and_zero() {
+                        assert_eq!($t::MIN + 1, add_one($t::MIN));
+                        assert_eq!(Some($t::MIN + 1), checked_add_one($t::MIN));
+                        assert_eq!(1, add_one(0 as $t));
+                        assert_eq!(1, wrapping_add_one(0 as $t));
+                    }
+                }
+            )*
+        };
+    }
+
+    boundary_tests!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+    #[test]
+    fn signed_minus_one() {
+        assert_eq!(0, add_one(-1i8));
+        assert_eq!(0, add_one(-1i64));
+        assert_eq!(Some(0), checked_add_one(-1i128));
+    }
+
+    #[test]
+    fn add_n_at_once() {
+        assert_eq!(Some(255), 5u8.checked_add_n(250));
+        assert_eq!(None, 6u8.checked_add_n(250));
+        assert_eq!(None, 0i8.checked_add_n(300));
+        assert_eq!(Some(1), (-1i32).checked_add_n(2));
+        assert_eq!(Some(u64::from(u32::MAX) + 1), 1u64.checked_add_n(u32::MAX));
+        assert_eq!(Some(BigInt::from(u32::MAX) - 7), BigInt::from(-7).checked_add_n(u32::MAX));
+    }
+
+    #[test]
+    fn big_integers_never_overflow() {
+        let max = BigUint::from(u128::MAX);
+        let expected = BigUint::from(u128::MAX) + 1u8;
+        assert_eq!(expected, add_one(max.clone()));
+        assert_eq!(Some(expected.clone()), checked_add_one(max.clone()));
+        assert_eq!(expected.clone(), saturating_add_one(max.clone()));
+        assert_eq!(expected, wrapping_add_one(max));
+
+        let min = BigInt::from(i128::MIN) - 1;
+        assert_eq!(BigInt::from(i128::MIN), add_one(min));
+        assert_eq!(BigInt::from(0), add_one(BigInt::from(-1)));
+    }
+}
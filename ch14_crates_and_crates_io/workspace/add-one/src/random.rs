@@ -0,0 +1,127 @@
+//! Random increments built on [`AddOne`].
+//!
+//! Increments only go up: a step is a random amount between `0` and
+//! `max_delta`, never negative, so a walk never decreases.
+//!
+//! Every function takes its random number generator from the caller or a
+//! seed, so the same seed always gives the same numbers.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::AddOne;
+
+/// Adds a random amount between `0` and `max_delta` (both included) to `x`,
+/// all at once through [`AddOne::checked_add_n`]. Returns `None` on overflow.
+pub fn random_increment<T: AddOne, R: Rng>(x: T, max_delta: u32, rng: &mut R) -> Option<T> {
+    let delta = rng.gen_range(0..=max_delta);
+    x.checked_add_n(delta)
+}
+
+/// An endless sequence of random increments, ending early only if the next
+/// value would overflow.
+pub struct RandomWalk<T, R> {
+    next: Option<T>,
+    max_delta: u32,
+    rng: R,
+}
+
+impl<T: AddOne + Clone, R: Rng> RandomWalk<T, R> {
+    /// Creates a walk starting at `start` (the first value yielded) that
+    /// takes its increments from `rng`.
+    pub fn with_rng(start: T, max_delta: u32, rng: R) -> RandomWalk<T, R> {
+        RandomWalk {
+            next: Some(start),
+            max_delta,
+            rng,
+        }
+    }
+}
+
+impl<T: AddOne + Clone, R: Rng> Iterator for RandomWalk<T, R> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let current = self.next.take()?;
+        self.next = random_increment(current.clone(), self.max_delta, &mut self.rng);
+        Some(current)
+    }
+}
+
+/// Creates a reproducible random walk from a seed.
+///
+/// It uses ChaCha8, whose output is fixed for a given seed, unlike
+/// `rand::rngs::StdRng`, which may change between versions of `rand`.
+///
+/// # Examples
+/// ```
+/// let walk: Vec<i32> = add_one::random::random_walk(0, 3, 42).take(5).collect();
+/// let again: Vec<i32> = add_one::random::random_walk(0, 3, 42).take(5).collect();
+///
+/// assert_eq!(walk, again);
+/// assert_eq!(0, walk[0]);
+/// assert!(walk.windows(2).all(|w| w[1] - w[0] <= 3));
+/// ```
+pub fn random_walk<T: AddOne + Clone>(
+    start: T,
+    max_delta: u32,
+    seed: u64,
+) -> RandomWalk<T, ChaCha8Rng> {
+    RandomWalk::with_rng(start, max_delta, ChaCha8Rng::seed_from_u64(seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_seed_pins_the_sequence() {
+        let walk: Vec<i32> = random_walk(0, 5, 2024).take(10).collect();
+        assert_eq!(vec![0, 4, 5, 9, 13, 15, 20, 24, 25, 30], walk);
+    }
+
+    #[test]
+    fn different_seeds_give_different_walks() {
+        let a: Vec<u64> = random_walk(0, 100, 1).take(20).collect();
+        let b: Vec<u64> = random_walk(0, 100, 2).take(20).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn zero_delta_stays_in_place() {
+        let walk: Vec<i8> = random_walk(7, 0, 0).take(5).collect();
+        assert_eq!(vec![7; 5], walk);
+    }
+
+    #[test]
+    fn walk_stops_before_overflow() {
+        let walk: Vec<u8> = random_walk(250, 10, 9).collect();
+        assert!(walk.len() > 1);
+        assert!(walk.windows(2).all(|w| w[0] <= w[1]));
+        assert!(walk.iter().all(|&x| x >= 250));
+    }
+
+    #[test]
+    fn random_increment_with_any_rng() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for _ in 0..100 {
+            let x = random_increment(10u32, 4, &mut rng).unwrap();
+            assert!((10..=14).contains(&x));
+        }
+        assert_eq!(Some(u32::MAX), random_increment(u32::MAX, 0, &mut rng));
+        for _ in 0..100 {
+            let x = random_increment(u8::MAX, 4, &mut rng);
+            assert!(x.is_none() || x == Some(u8::MAX));
+        }
+    }
+
+    #[test]
+    fn huge_deltas_take_a_single_step() {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let x = random_increment(0u64, u32::MAX, &mut rng).unwrap();
+        assert!(x <= u64::from(u32::MAX));
+
+        let walk: Vec<u64> = random_walk(0, u32::MAX, 8).take(1000).collect();
+        assert!(walk.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
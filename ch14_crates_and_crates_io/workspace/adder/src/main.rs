@@ -1,11 +1,54 @@
-use add_one;
-use rand;
+use std::io::{self, Write};
+
+use calc::{CalcError, Calculator};
+
+const HELP: &str = "Enter an expression like '2 * (x + 1)' or an assignment like 'x = 3'.
+Operators: + - * / % ^    Functions: abs(x), min(x, y), max(x, y), add_one(x)
+Commands: history, vars, help, quit";
 
 fn main() {
-    let n = 10;
-    println!(
-        "Hello World! {} plus one is {}",
-        n,
-        add_one::add_one(n)
-    );
+    let mut calc = Calculator::new();
+
+    // add_one viene de la crate add-one del mismo workspace.
+    calc.define_function("add_one", 1, |args| {
+        add_one::checked_add_one(args[0]).ok_or(CalcError::Overflow)
+    });
+
+    println!("Calculator. Type 'help' for help.");
+
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        let mut line = String::new();
+        let bytes = io::stdin()
+            .read_line(&mut line)
+            .expect("Failed to read line");
+
+        // 0 bytes: EOF (Ctrl+D)
+        if bytes == 0 {
+            println!();
+            break;
+        }
+
+        match line.trim() {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => println!("{}", HELP),
+            "history" => {
+                for (i, (input, result)) in calc.history().iter().enumerate() {
+                    println!("{:>3}: {} = {}", i + 1, input, result);
+                }
+            }
+            "vars" => {
+                for (name, value) in calc.variables() {
+                    println!("{} = {}", name, value);
+                }
+            }
+            input => match calc.eval(input) {
+                Ok(value) => println!("{}", value),
+                Err(e) => println!("error: {}", e),
+            },
+        }
+    }
 }
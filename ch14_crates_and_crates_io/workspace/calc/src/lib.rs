@@ -0,0 +1,273 @@
+//! # Calc
+//!
+//! An integer calculator with variables, functions and a history of results.
+
+use std::collections::HashMap;
+use std::fmt;
+
+pub mod parser;
+pub mod token;
+
+use parser::{BinOp, Expr, Statement};
+use token::Token;
+
+/// Everything that can go wrong while evaluating an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalcError {
+    UnexpectedChar(char, usize),
+    UnexpectedToken(Token),
+    UnexpectedEnd,
+    TooDeep,
+    UnknownVariable(String),
+    UnknownFunction(String),
+    WrongArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    DivisionByZero,
+    NegativeExponent,
+    Overflow,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalcError::UnexpectedChar(c, pos) => {
+                write!(f, "unexpected character '{}' at position {}", c, pos)
+            }
+            CalcError::UnexpectedToken(token) => write!(f, "unexpected {:?}", token),
+            CalcError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            CalcError::TooDeep => write!(f, "expression nested too deeply"),
+            CalcError::UnknownVariable(name) => write!(f, "unknown variable '{}'", name),
+            CalcError::UnknownFunction(name) => write!(f, "unknown function '{}'", name),
+            CalcError::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} takes {} argument(s), but {} were given",
+                name, expected, found
+            ),
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::NegativeExponent => write!(f, "negative exponent"),
+            CalcError::Overflow => write!(f, "overflow"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// A function callable from expressions. It gets as many arguments as the
+/// arity it was defined with.
+pub type Function = fn(&[i64]) -> Result<i64, CalcError>;
+
+/// Evaluates expressions, keeping variables and a history between inputs.
+///
+/// # Examples
+/// ```
+/// let mut calc = calc::Calculator::new();
+///
+/// calc.eval("x = 2 + 3 * 4").unwrap();
+/// assert_eq!(Ok(28), calc.eval("x * 2"));
+/// assert_eq!(Ok(29), calc.eval("ans + 1"));
+/// ```
+pub struct Calculator {
+    variables: HashMap<String, i64>,
+    functions: HashMap<String, (usize, Function)>,
+    history: Vec<(String, i64)>,
+}
+
+impl Calculator {
+    /// Creates a calculator with the built-in functions `abs`, `min` and `max`.
+    pub fn new() -> Calculator {
+        let mut calc = Calculator {
+            variables: HashMap::new(),
+            functions: HashMap::new(),
+            history: Vec::new(),
+        };
+
+        calc.define_function("abs", 1, |args| args[0].checked_abs().ok_or(CalcError::Overflow));
+        calc.define_function("min", 2, |args| Ok(args[0].min(args[1])));
+        calc.define_function("max", 2, |args| Ok(args[0].max(args[1])));
+        calc
+    }
+
+    /// Defines a function, replacing any previous one with the same name.
+    pub fn define_function(&mut self, name: &str, arity: usize, function: Function) {
+        self.functions.insert(name.to_string(), (arity, function));
+    }
+
+    /// Evaluates a line of input: an expression like `2 * (x + 1)`, or an
+    /// assignment like `x = 3`. The result is saved to the history and to
+    /// the `ans` variable.
+    pub fn eval(&mut self, input: &str) -> Result<i64, CalcError> {
+        let tokens = token::tokenize(input)?;
+
+        let value = match parser::parse(&tokens)? {
+            Statement::Assign(name, expr) => {
+                let value = self.eval_expr(&expr)?;
+                self.variables.insert(name, value);
+                value
+            }
+            Statement::Expr(expr) => self.eval_expr(&expr)?,
+        };
+
+        self.variables.insert("ans".to_string(), value);
+        self.history.push((input.trim().to_string(), value));
+        Ok(value)
+    }
+
+    pub fn variable(&self, name: &str) -> Option<i64> {
+        self.variables.get(name).copied()
+    }
+
+    /// Every variable with its value, sorted by name.
+    pub fn variables(&self) -> Vec<(&str, i64)> {
+        let mut variables: Vec<(&str, i64)> = self
+            .variables
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+            .collect();
+        variables.sort();
+        variables
+    }
+
+    /// Every input evaluated successfully, with its result, oldest first.
+    pub fn history(&self) -> &[(String, i64)] {
+        &self.history
+    }
+
+    fn eval_expr(&self, expr: &Expr) -> Result<i64, CalcError> {
+        match expr {
+            Expr::Number(n) => Ok(*n),
+            Expr::Var(name) => self
+                .variable(name)
+                .ok_or_else(|| CalcError::UnknownVariable(name.clone())),
+            Expr::Neg(expr) => self.eval_expr(expr)?.checked_neg().ok_or(CalcError::Overflow),
+            Expr::Binary(op, lhs, rhs) => {
+                let lhs = self.eval_expr(lhs)?;
+                let rhs = self.eval_expr(rhs)?;
+                apply(*op, lhs, rhs)
+            }
+            Expr::Call(name, args) => {
+                let (arity, function) = self
+                    .functions
+                    .get(name)
+                    .ok_or_else(|| CalcError::UnknownFunction(name.clone()))?;
+
+                if args.len() != *arity {
+                    return Err(CalcError::WrongArity {
+                        name: name.clone(),
+                        expected: *arity,
+                        found: args.len(),
+                    });
+                }
+
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval_expr(arg))
+                    .collect::<Result<Vec<i64>, CalcError>>()?;
+                function(&args)
+            }
+        }
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Calculator::new()
+    }
+}
+
+fn apply(op: BinOp, lhs: i64, rhs: i64) -> Result<i64, CalcError> {
+    let result = match op {
+        BinOp::Add => lhs.checked_add(rhs),
+        BinOp::Sub => lhs.checked_sub(rhs),
+        BinOp::Mul => lhs.checked_mul(rhs),
+        BinOp::Div | BinOp::Rem if rhs == 0 => return Err(CalcError::DivisionByZero),
+        BinOp::Div => lhs.checked_div(rhs),
+        BinOp::Rem => lhs.checked_rem(rhs),
+        BinOp::Pow if rhs < 0 => return Err(CalcError::NegativeExponent),
+        BinOp::Pow => u32::try_from(rhs).ok().and_then(|exp| lhs.checked_pow(exp)),
+    };
+
+    result.ok_or(CalcError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let mut calc = Calculator::new();
+        assert_eq!(Ok(7), calc.eval("1 + 2 * 3"));
+        assert_eq!(Ok(9), calc.eval("(1 + 2) * 3"));
+        assert_eq!(Ok(-4), calc.eval("-2 ^ 2"));
+        assert_eq!(Ok(512), calc.eval("2 ^ 3 ^ 2"));
+        assert_eq!(Ok(2), calc.eval("17 % 5"));
+        assert_eq!(Ok(-3), calc.eval("-7 / 2"));
+        assert_eq!(Ok(4), calc.eval("10 - 3 - 3"));
+    }
+
+    #[test]
+    fn variables_and_ans() {
+        let mut calc = Calculator::new();
+        assert_eq!(Ok(5), calc.eval("x = 5"));
+        assert_eq!(Ok(10), calc.eval("y = x * 2"));
+        assert_eq!(Ok(15), calc.eval("x + y"));
+        assert_eq!(Ok(16), calc.eval("ans + 1"));
+        assert_eq!(vec![("ans", 16), ("x", 5), ("y", 10)], calc.variables());
+        assert_eq!(
+            Err(CalcError::UnknownVariable("z".to_string())),
+            calc.eval("z + 1")
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let mut calc = Calculator::new();
+        assert_eq!(Ok(3), calc.eval("max(abs(-3), min(1, 2))"));
+
+        calc.define_function("double", 1, |args| Ok(args[0] * 2));
+        assert_eq!(Ok(8), calc.eval("double(4)"));
+        assert_eq!(
+            Err(CalcError::WrongArity {
+                name: "double".to_string(),
+                expected: 1,
+                found: 2
+            }),
+            calc.eval("double(1, 2)")
+        );
+        assert_eq!(
+            Err(CalcError::UnknownFunction("triple".to_string())),
+            calc.eval("triple(1)")
+        );
+    }
+
+    #[test]
+    fn arithmetic_errors() {
+        let mut calc = Calculator::new();
+        assert_eq!(Err(CalcError::DivisionByZero), calc.eval("1 / 0"));
+        assert_eq!(Err(CalcError::DivisionByZero), calc.eval("1 % (2 - 2)"));
+        assert_eq!(Err(CalcError::NegativeExponent), calc.eval("2 ^ -1"));
+        assert_eq!(Err(CalcError::Overflow), calc.eval("2 ^ 63"));
+        assert_eq!(Err(CalcError::Overflow), calc.eval("9223372036854775807 + 1"));
+        assert_eq!(Err(CalcError::Overflow), calc.eval("abs(-9223372036854775807 - 1)"));
+    }
+
+    #[test]
+    fn history_keeps_successful_inputs() {
+        let mut calc = Calculator::new();
+        calc.eval("1 + 1").unwrap();
+        calc.eval("1 / 0").unwrap_err();
+        calc.eval(" x = 3 ").unwrap();
+
+        assert_eq!(
+            &[("1 + 1".to_string(), 2), ("x = 3".to_string(), 3)],
+            calc.history()
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use crate::token::Token;
+use crate::CalcError;
+
+/// A binary operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+}
+
+impl BinOp {
+    fn from_token(token: &Token) -> Option<BinOp> {
+        match token {
+            Token::Plus => Some(BinOp::Add),
+            Token::Minus => Some(BinOp::Sub),
+            Token::Star => Some(BinOp::Mul),
+            Token::Slash => Some(BinOp::Div),
+            Token::Percent => Some(BinOp::Rem),
+            Token::Caret => Some(BinOp::Pow),
+            _ => None,
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 2,
+            BinOp::Pow => 3,
+        }
+    }
+
+    fn right_associative(self) -> bool {
+        self == BinOp::Pow
+    }
+}
+
+/// An expression tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// A line of input: an assignment to a variable or a plain expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Assign(String, Expr),
+    Expr(Expr),
+}
+
+/// How deep parentheses, unary minus and `^` can nest before parsing fails
+/// with [`CalcError::TooDeep`].
+pub const MAX_DEPTH: usize = 256;
+
+/// Parses a list of tokens into a statement.
+pub fn parse(tokens: &[Token]) -> Result<Statement, CalcError> {
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+
+    let statement = match tokens {
+        [Token::Ident(name), Token::Equals, ..] => {
+            parser.pos = 2;
+            Statement::Assign(name.clone(), parser.expr(0)?)
+        }
+        _ => Statement::Expr(parser.expr(0)?),
+    };
+
+    match parser.next() {
+        None => Ok(statement),
+        Some(token) => Err(CalcError::UnexpectedToken(token.clone())),
+    }
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    // Cuántas llamadas a expr hay abiertas, para no desbordar la pila
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CalcError> {
+        match self.next() {
+            Some(token) if *token == expected => Ok(()),
+            Some(token) => Err(CalcError::UnexpectedToken(token.clone())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    /* Precedence climbing: se parsea un operando y, mientras el siguiente
+     * operador tenga al menos `min_precedence`, se parsea su lado derecho con
+     * una precedencia mayor (o igual si es asociativo por la derecha, como ^).
+     *
+     * Toda la recursión pasa por aquí, así que aquí se limita la profundidad.
+     */
+    fn expr(&mut self, min_precedence: u8) -> Result<Expr, CalcError> {
+        if self.depth == MAX_DEPTH {
+            return Err(CalcError::TooDeep);
+        }
+        self.depth += 1;
+        let expr = self.climb(min_precedence);
+        self.depth -= 1;
+        expr
+    }
+
+    fn climb(&mut self, min_precedence: u8) -> Result<Expr, CalcError> {
+        let mut lhs = self.atom()?;
+
+        while let Some(op) = self.peek().and_then(BinOp::from_token) {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pos += 1;
+
+            let next_precedence = if op.right_associative() {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let rhs = self.expr(next_precedence)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    fn atom(&mut self) -> Result<Expr, CalcError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(*n)),
+            // El menos unario se aplica después de ^, así -2^2 es -(2^2).
+            Some(Token::Minus) => Ok(Expr::Neg(Box::new(self.expr(BinOp::Pow.precedence())?))),
+            Some(Token::LParen) => {
+                let expr = self.expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(name)) => {
+                if self.peek() != Some(&Token::LParen) {
+                    return Ok(Expr::Var(name.clone()));
+                }
+                self.pos += 1;
+
+                let mut args = Vec::new();
+                if self.peek() == Some(&Token::RParen) {
+                    self.pos += 1;
+                    return Ok(Expr::Call(name.clone(), args));
+                }
+                loop {
+                    args.push(self.expr(0)?);
+                    match self.next() {
+                        Some(Token::Comma) => continue,
+                        Some(Token::RParen) => break,
+                        Some(token) => return Err(CalcError::UnexpectedToken(token.clone())),
+                        None => return Err(CalcError::UnexpectedEnd),
+                    }
+                }
+                Ok(Expr::Call(name.clone(), args))
+            }
+            Some(token) => Err(CalcError::UnexpectedToken(token.clone())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::tokenize;
+
+    fn parse_str(input: &str) -> Result<Statement, CalcError> {
+        parse(&tokenize(input)?)
+    }
+
+    fn num(n: i64) -> Box<Expr> {
+        Box::new(Expr::Number(n))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            Ok(Statement::Expr(Expr::Binary(
+                BinOp::Add,
+                num(1),
+                Box::new(Expr::Binary(BinOp::Mul, num(2), num(3)))
+            ))),
+            parse_str("1 + 2 * 3")
+        );
+    }
+
+    #[test]
+    fn left_associative() {
+        assert_eq!(
+            Ok(Statement::Expr(Expr::Binary(
+                BinOp::Sub,
+                Box::new(Expr::Binary(BinOp::Sub, num(1), num(2))),
+                num(3)
+            ))),
+            parse_str("1 - 2 - 3")
+        );
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        assert_eq!(
+            Ok(Statement::Expr(Expr::Binary(
+                BinOp::Pow,
+                num(2),
+                Box::new(Expr::Binary(BinOp::Pow, num(3), num(2)))
+            ))),
+            parse_str("2 ^ 3 ^ 2")
+        );
+    }
+
+    #[test]
+    fn assignment_and_calls() {
+        assert_eq!(
+            Ok(Statement::Assign(
+                "x".to_string(),
+                Expr::Call("max".to_string(), vec![Expr::Var("y".to_string()), Expr::Number(1)])
+            )),
+            parse_str("x = max(y, 1)")
+        );
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(Err(CalcError::UnexpectedEnd), parse_str("1 +"));
+        assert_eq!(Err(CalcError::UnexpectedEnd), parse_str("(1 + 2"));
+        assert_eq!(Err(CalcError::UnexpectedToken(Token::RParen)), parse_str("1 + 2)"));
+        assert_eq!(Err(CalcError::UnexpectedToken(Token::Equals)), parse_str("1 = 2"));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(parse_str(&nested(MAX_DEPTH - 1)).is_ok());
+        assert_eq!(Err(CalcError::TooDeep), parse_str(&nested(MAX_DEPTH)));
+
+        // Lo bastante hondo para desbordar la pila sin el límite
+        assert_eq!(Err(CalcError::TooDeep), parse_str(&nested(200_000)));
+        assert_eq!(Err(CalcError::TooDeep), parse_str(&format!("{}1", "-".repeat(200_000))));
+        assert_eq!(Err(CalcError::TooDeep), parse_str(&"2^".repeat(200_000)));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::CalcError;
+
+/// A token of an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Number(i64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+    Equals,
+}
+
+/// Splits the input into tokens, skipping whitespace.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(pos, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '0'..='9' => {
+                let mut number = String::new();
+                while let Some(&(_, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    number.push(d);
+                    chars.next();
+                }
+                let value = number.parse().map_err(|_| CalcError::Overflow)?;
+                tokens.push(Token::Number(value));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&(_, d)) = chars.peek() {
+                    if !(d.is_alphanumeric() || d == '_') {
+                        break;
+                    }
+                    ident.push(d);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '%' => Token::Percent,
+            '^' => Token::Caret,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ',' => Token::Comma,
+            '=' => Token::Equals,
+            other => return Err(CalcError::UnexpectedChar(other, pos)),
+        };
+        tokens.push(token);
+        chars.next();
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_expression() {
+        let tokens = tokenize("x1 = max(2, 30) ^ -4").unwrap();
+        assert_eq!(
+            vec![
+                Token::Ident("x1".to_string()),
+                Token::Equals,
+                Token::Ident("max".to_string()),
+                Token::LParen,
+                Token::Number(2),
+                Token::Comma,
+                Token::Number(30),
+                Token::RParen,
+                Token::Caret,
+                Token::Minus,
+                Token::Number(4),
+            ],
+            tokens
+        );
+    }
+
+    #[test]
+    fn unexpected_char() {
+        assert_eq!(Err(CalcError::UnexpectedChar('$', 4)), tokenize("1 + $"));
+    }
+
+    #[test]
+    fn number_too_big() {
+        assert_eq!(Err(CalcError::Overflow), tokenize("99999999999999999999"));
+    }
+}
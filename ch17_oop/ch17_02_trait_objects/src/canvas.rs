@@ -0,0 +1,219 @@
+use std::fmt;
+
+/* El lienzo es una cuadrícula de caracteres, como una terminal: los
+ * componentes escriben en sus celdas y al final se muestra entero, línea a
+ * línea. Todo lo que cae fuera del lienzo se recorta, así que un componente
+ * que no cabe no da error, simplemente no se ve entero.
+ */
+
+/// Width and height, in character cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A rectangle of cells, from its top left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// The part of the rectangle inside its border.
+    pub fn inner(&self) -> Rect {
+        Rect {
+            x: self.x + 1,
+            y: self.y + 1,
+            width: self.width.saturating_sub(2),
+            height: self.height.saturating_sub(2),
+        }
+    }
+}
+
+/// Lines used to draw borders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    #[default]
+    Single,
+    Double,
+}
+
+impl BorderStyle {
+    // Esquinas (arriba izquierda, arriba derecha, abajo izquierda, abajo
+    // derecha), línea horizontal y línea vertical
+    fn chars(self) -> [char; 6] {
+        match self {
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+        }
+    }
+}
+
+/// A grid of characters that components draw into.
+///
+/// # Examples
+/// ```
+/// use ch17_02_trait_objects::canvas::{BorderStyle, Canvas, Rect};
+///
+/// let mut canvas = Canvas::new(8, 3);
+/// canvas.border(Rect::new(0, 0, 8, 3), BorderStyle::Single);
+/// canvas.text(2, 1, "hi");
+///
+/// assert_eq!("┌──────┐\n│ hi   │\n└──────┘\n", canvas.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    cells: Vec<char>,
+}
+
+impl Canvas {
+    /// An empty canvas, filled with spaces.
+    pub fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width,
+            height,
+            cells: vec![' '; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The character at a cell, or `None` outside the canvas.
+    pub fn get(&self, x: u32, y: u32) -> Option<char> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    pub fn put(&mut self, x: u32, y: u32, c: char) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = c;
+        }
+    }
+
+    /// Writes text from a cell to the right, on a single line.
+    pub fn text(&mut self, x: u32, y: u32, text: &str) {
+        for (i, c) in text.chars().enumerate() {
+            self.put(x + i as u32, y, c);
+        }
+    }
+
+    /// Writes text on a line, but no further than `width` cells.
+    pub fn text_clipped(&mut self, x: u32, y: u32, width: u32, text: &str) {
+        let clipped: String = text.chars().take(width as usize).collect();
+        self.text(x, y, &clipped);
+    }
+
+    pub fn fill(&mut self, area: Rect, c: char) {
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                self.put(x, y, c);
+            }
+        }
+    }
+
+    /// Draws a border on the edge of the area. Areas smaller than 2×2 have
+    /// no room for one.
+    pub fn border(&mut self, area: Rect, style: BorderStyle) {
+        if area.width < 2 || area.height < 2 {
+            return;
+        }
+
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = style.chars();
+        let right = area.x + area.width - 1;
+        let bottom = area.y + area.height - 1;
+
+        for x in area.x + 1..right {
+            self.put(x, area.y, horizontal);
+            self.put(x, bottom, horizontal);
+        }
+        for y in area.y + 1..bottom {
+            self.put(area.x, y, vertical);
+            self.put(right, y, vertical);
+        }
+        self.put(area.x, area.y, top_left);
+        self.put(right, area.y, top_right);
+        self.put(area.x, bottom, bottom_left);
+        self.put(right, bottom, bottom_right);
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some((y * self.width + x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+// Cada fila en una línea, sin los espacios del final
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1) as usize) {
+            let line: String = row.iter().collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drawing_outside_is_ignored() {
+        let mut canvas = Canvas::new(4, 2);
+        canvas.text(2, 0, "abcdef");
+        canvas.put(9, 9, 'x');
+        canvas.fill(Rect::new(3, 1, 5, 5), '#');
+
+        assert_eq!(Some('b'), canvas.get(3, 0));
+        assert_eq!(None, canvas.get(4, 0));
+        assert_eq!("  ab\n   #\n", canvas.to_string());
+    }
+
+    #[test]
+    fn borders() {
+        let mut canvas = Canvas::new(5, 4);
+        canvas.border(Rect::new(0, 0, 5, 4), BorderStyle::Double);
+        // Demasiado pequeño para un borde
+        canvas.border(Rect::new(1, 1, 1, 2), BorderStyle::Single);
+
+        assert_eq!("╔═══╗\n║   ║\n║   ║\n╚═══╝\n", canvas.to_string());
+        assert_eq!(Rect::new(1, 1, 3, 2), Rect::new(0, 0, 5, 4).inner());
+    }
+
+    #[test]
+    fn rects_contain_their_cells() {
+        let rect = Rect::new(2, 1, 3, 2);
+        assert!(rect.contains(2, 1));
+        assert!(rect.contains(4, 2));
+        assert!(!rect.contains(5, 2));
+        assert!(!rect.contains(1, 1));
+        assert!(!rect.contains(2, 3));
+    }
+}
@@ -0,0 +1,30 @@
+/* El Screen no lee la terminal: recibe los eventos de cualquier iterador.
+ * Leer el teclado y el ratón tecla a tecla necesitaría poner la terminal en
+ * modo raw (con una biblioteca como crossterm) y convertir lo leído en estos
+ * eventos. Aquí, tanto main como los tests los escriben a mano en un Vec.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Tab,
+    /// Shift + Tab.
+    BackTab,
+    Enter,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Char(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Event {
+    Key(Key),
+    /// A mouse click on a cell. The screen gives it to the component that was
+    /// clicked, with the position relative to the component's corner.
+    Click {
+        x: u32,
+        y: u32,
+    },
+}
@@ -1,17 +1,277 @@
+use std::io::{self, Write};
+
+pub mod canvas;
+pub mod events;
+
+use canvas::{BorderStyle, Canvas, Rect, Size};
+use events::{Event, Key};
+
+/* Cada componente dice cuánto espacio necesita (size) y sabe dibujarse en el
+ * área que le toca (draw). El Screen reparte las áreas según su Layout y
+ * dibuja todos los componentes en un mismo lienzo, que luego se muestra en la
+ * terminal o se convierte en un String.
+ *
+ * Los eventos van por otro trait, Handle: el Screen se queda con Tab y
+ * Shift+Tab para mover el foco, manda el resto de teclas al componente con
+ * el foco, y los clics al componente que está debajo.
+ */
+
 pub trait Draw {
-    fn draw(&self);
+    /// The cells the component needs.
+    fn size(&self) -> Size;
+
+    /// Draws the component in `area`, which has the size it asked for unless
+    /// the screen is too small.
+    fn draw(&self, canvas: &mut Canvas, area: Rect);
+
+    /// Draws the component when it has the focus.
+    fn draw_focused(&self, canvas: &mut Canvas, area: Rect) {
+        self.draw(canvas, area);
+    }
 }
 
+/// Reacts to events. Components that only show something can implement it
+/// with no methods: they don't take the focus and ignore every event.
+pub trait Handle {
+    /// Whether Tab and clicks can give the focus to the component.
+    fn focusable(&self) -> bool {
+        false
+    }
+
+    /// Handles an event, and returns whether it was used. Clicks come with
+    /// the position relative to the component.
+    fn handle(&mut self, _event: &Event) -> bool {
+        false
+    }
+}
+
+/// What the screen holds: anything that can be drawn and handle events.
+pub trait Component: Draw + Handle {}
+
+impl<T: Draw + Handle> Component for T {}
+
+/// How the screen places its components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One below the other, with `gap` empty rows between them.
+    Vertical { gap: u32 },
+    /// Side by side, with `gap` empty columns between them.
+    Horizontal { gap: u32 },
+}
+
+impl Default for Layout {
+    fn default() -> Layout {
+        Layout::Vertical { gap: 0 }
+    }
+}
+
+/// # Examples
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use ch17_02_trait_objects::events::{Event, Key};
+/// use ch17_02_trait_objects::{Button, Screen};
+///
+/// let clicks = Rc::new(Cell::new(0));
+/// let counter = Rc::clone(&clicks);
+///
+/// let mut screen = Screen::new(12, 3);
+/// screen.components.push(Box::new(
+///     Button::new(6, 3, "ok").on_click(move || counter.set(counter.get() + 1)),
+/// ));
+/// assert_eq!("┌────┐\n│ ok │\n└────┘\n", screen.render_to_string());
+///
+/// screen.dispatch(Event::Key(Key::Tab));
+/// screen.dispatch(Event::Key(Key::Enter));
+/// assert_eq!(1, clicks.get());
+/// assert_eq!("╔════╗\n║ ok ║\n╚════╝\n", screen.render_to_string());
+/// ```
 pub struct Screen {
     // dynamic dispatch
-    pub components: Vec<Box<dyn Draw>>,
+    pub components: Vec<Box<dyn Component>>,
+    width: u32,
+    height: u32,
+    layout: Layout,
+    focus: Option<usize>,
 }
 
 impl Screen {
-    pub fn run(&self) {
-        // dynamic dispatch
+    pub fn new(width: u32, height: u32) -> Screen {
+        Screen {
+            components: Vec::new(),
+            width,
+            height,
+            layout: Layout::default(),
+            focus: None,
+        }
+    }
+
+    pub fn with_layout(mut self, layout: Layout) -> Screen {
+        self.layout = layout;
+        self
+    }
+
+    /// The area of each component, in the same order. Components that don't
+    /// fit are cut at the edge of the screen.
+    pub fn layout(&self) -> Vec<Rect> {
+        let mut areas = Vec::new();
+        let mut offset = 0;
+
         for component in self.components.iter() {
-            component.draw();
+            let Size { width, height } = component.size();
+            let area = match self.layout {
+                Layout::Vertical { gap } => {
+                    let area = Rect::new(0, offset, width, height);
+                    offset += height + gap;
+                    area
+                }
+                Layout::Horizontal { gap } => {
+                    let area = Rect::new(offset, 0, width, height);
+                    offset += width + gap;
+                    area
+                }
+            };
+            areas.push(area);
+        }
+        areas
+    }
+
+    /// Draws every component into a new canvas the size of the screen.
+    pub fn render(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width, self.height);
+        let focus = self.focused();
+        // dynamic dispatch
+        for (i, (component, area)) in self.components.iter().zip(self.layout()).enumerate() {
+            if focus == Some(i) {
+                component.draw_focused(&mut canvas, area);
+            } else {
+                component.draw(&mut canvas, area);
+            }
+        }
+        canvas
+    }
+
+    /// The index of the component with the focus.
+    pub fn focused(&self) -> Option<usize> {
+        // components es público: el componente con el foco puede haber
+        // desaparecido o cambiado por otro
+        self.focus.filter(|&i| {
+            self.components
+                .get(i)
+                .is_some_and(|component| component.focusable())
+        })
+    }
+
+    /// Gives the focus to a component, if it can take it. Returns whether it
+    /// did.
+    pub fn set_focus(&mut self, index: usize) -> bool {
+        match self.components.get(index) {
+            Some(component) if component.focusable() => {
+                self.focus = Some(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Handles an event as if it came from the terminal, and returns whether
+    /// something used it.
+    pub fn dispatch(&mut self, event: Event) -> bool {
+        self.focus = self.focused();
+
+        match event {
+            Event::Key(Key::Tab) => self.move_focus(true),
+            Event::Key(Key::BackTab) => self.move_focus(false),
+            Event::Key(_) => match self.focus.and_then(|i| self.components.get_mut(i)) {
+                Some(component) => component.handle(&event),
+                None => false,
+            },
+            Event::Click { x, y } => {
+                // Si hay componentes encima de otros, el último es el que se ve
+                let clicked = self
+                    .layout()
+                    .into_iter()
+                    .enumerate()
+                    .rev()
+                    .find(|(_, area)| area.contains(x, y));
+                match clicked {
+                    Some((i, area)) => {
+                        self.set_focus(i);
+                        self.components[i].handle(&Event::Click {
+                            x: x - area.x,
+                            y: y - area.y,
+                        })
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    /// Handles the events one by one, drawing the screen at the start and
+    /// after each of them, until they run out or Esc is pressed.
+    pub fn run_with<I, W>(&mut self, events: I, out: &mut W) -> io::Result<()>
+    where
+        I: IntoIterator<Item = Event>,
+        W: Write,
+    {
+        self.draw_to(out)?;
+        for event in events {
+            if event == Event::Key(Key::Esc) {
+                break;
+            }
+            self.dispatch(event);
+            self.draw_to(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn render_to_string(&self) -> String {
+        self.render().to_string()
+    }
+
+    /// Writes the screen to `out`, clearing the terminal first.
+    pub fn draw_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        // Borra la pantalla y vuelve a la esquina de arriba a la izquierda
+        write!(out, "\x1b[2J\x1b[H{}", self.render())?;
+        out.flush()
+    }
+
+    /// Draws the screen on the terminal.
+    pub fn run(&self) -> io::Result<()> {
+        self.draw_to(&mut io::stdout().lock())
+    }
+
+    // Pasa al siguiente (o al anterior) componente que acepta el foco, dando
+    // la vuelta al llegar al final
+    fn move_focus(&mut self, forward: bool) -> bool {
+        let count = self.components.len();
+        if count == 0 {
+            return false;
+        }
+
+        let start = match (self.focus, forward) {
+            (Some(i), true) => i + 1,
+            (Some(i), false) => i + count - 1,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        let next = (0..count)
+            .map(|step| {
+                if forward {
+                    (start + step) % count
+                } else {
+                    (start + count - step) % count
+                }
+            })
+            .find(|&i| self.components[i].focusable());
+
+        match next {
+            Some(i) => {
+                self.focus = Some(i);
+                true
+            }
+            None => false,
         }
     }
 }
@@ -20,11 +280,180 @@ pub struct Button {
     pub width: u32,
     pub height: u32,
     pub label: String,
+    on_click: Option<Box<dyn FnMut()>>,
 }
 
-// static dispatch  
+impl Button {
+    pub fn new(width: u32, height: u32, label: &str) -> Button {
+        Button {
+            width,
+            height,
+            label: String::from(label),
+            on_click: None,
+        }
+    }
+
+    /// Calls `f` every time the button is clicked, or pressed with Enter or
+    /// space while it has the focus.
+    pub fn on_click<F: FnMut() + 'static>(mut self, f: F) -> Button {
+        self.on_click = Some(Box::new(f));
+        self
+    }
+
+    pub fn click(&mut self) {
+        if let Some(on_click) = self.on_click.as_mut() {
+            on_click();
+        }
+    }
+
+    fn draw_with(&self, canvas: &mut Canvas, area: Rect, style: BorderStyle) {
+        canvas.border(area, style);
+
+        let inner = area.inner();
+        let label_width = (self.label.chars().count() as u32).min(inner.width);
+        let x = inner.x + (inner.width - label_width) / 2;
+        let y = inner.y + inner.height.saturating_sub(1) / 2;
+        canvas.text_clipped(x, y, label_width, &self.label);
+    }
+}
+
+// static dispatch
 impl Draw for Button {
-    fn draw(&self) {
-        // draw button
+    // Al menos lo que ocupan el borde y la etiqueta con un espacio a cada lado
+    fn size(&self) -> Size {
+        Size {
+            width: self.width.max(self.label.chars().count() as u32 + 4),
+            height: self.height.max(3),
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, area: Rect) {
+        self.draw_with(canvas, area, BorderStyle::Single);
+    }
+
+    fn draw_focused(&self, canvas: &mut Canvas, area: Rect) {
+        self.draw_with(canvas, area, BorderStyle::Double);
+    }
+}
+
+impl Handle for Button {
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(Key::Enter | Key::Char(' ')) | Event::Click { .. } => {
+                self.click();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Recibe el índice y el texto de la opción elegida
+type OnChange = Box<dyn FnMut(usize, &str)>;
+
+/// A list of options with one of them selected.
+pub struct SelectBox {
+    pub width: u32,
+    pub height: u32,
+    pub options: Vec<String>,
+    selected: usize,
+    on_change: Option<OnChange>,
+}
+
+impl SelectBox {
+    /// A select box with the first option selected.
+    pub fn new(width: u32, height: u32, options: &[&str]) -> SelectBox {
+        SelectBox {
+            width,
+            height,
+            options: options.iter().map(|option| String::from(*option)).collect(),
+            selected: 0,
+            on_change: None,
+        }
+    }
+
+    /// Calls `f` with the index and the text of the option every time the
+    /// selection changes.
+    pub fn on_change<F: FnMut(usize, &str) + 'static>(mut self, f: F) -> SelectBox {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects an option. Returns whether the selection changed; it doesn't
+    /// if the option was already selected or doesn't exist.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index == self.selected || index >= self.options.len() {
+            return false;
+        }
+
+        self.selected = index;
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(index, &self.options[index]);
+        }
+        true
+    }
+
+    fn draw_with(&self, canvas: &mut Canvas, area: Rect, style: BorderStyle) {
+        canvas.border(area, style);
+
+        let inner = area.inner();
+        for (i, option) in self.options.iter().enumerate().take(inner.height as usize) {
+            let mark = if i == self.selected { "(*)" } else { "( )" };
+            let line = format!("{} {}", mark, option);
+            canvas.text_clipped(
+                inner.x + 1,
+                inner.y + i as u32,
+                inner.width.saturating_sub(1),
+                &line,
+            );
+        }
+    }
+}
+
+impl Draw for SelectBox {
+    // Una fila por opción, con la marca "(*) " delante y un espacio a cada lado
+    fn size(&self) -> Size {
+        let longest = self
+            .options
+            .iter()
+            .map(|option| option.chars().count() as u32)
+            .max()
+            .unwrap_or(0);
+        Size {
+            width: self.width.max(longest + 8),
+            height: self.height.max(self.options.len() as u32 + 2),
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, area: Rect) {
+        self.draw_with(canvas, area, BorderStyle::Single);
+    }
+
+    fn draw_focused(&self, canvas: &mut Canvas, area: Rect) {
+        self.draw_with(canvas, area, BorderStyle::Double);
+    }
+}
+
+impl Handle for SelectBox {
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::Key(Key::Up) => self.selected > 0 && self.select(self.selected - 1),
+            Event::Key(Key::Down) => self.select(self.selected + 1),
+            // La primera fila es el borde
+            Event::Click { y, .. } if y >= 1 => self.select(y as usize - 1),
+            _ => false,
+        }
     }
 }
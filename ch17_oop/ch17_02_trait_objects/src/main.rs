@@ -3,42 +3,36 @@
 // https://doc.rust-lang.org/stable/book/ch17-02-trait-objects.html
 //////////////////////////////
 
-use ch17_02_trait_objects::{Button, Draw, Screen};
+use std::io;
 
-struct SelectBox {
-    width: u32,
-    height: u32,
-    options: Vec<String>,
-}
-
-// static dispatch
-impl Draw for SelectBox {
-    fn draw(&self) {
-        // draw button
-    }
-}
+use ch17_02_trait_objects::events::{Event, Key};
+use ch17_02_trait_objects::{Button, Layout, Screen, SelectBox};
 
 fn gui_lib() {
-    let screen = Screen {
-        components: vec![
-            Box::new(SelectBox {
-                width: 100,
-                height: 100,
-                options: vec![
-                    String::from("yes"),
-                    String::from("no"),
-                    String::from("maybe"),
-                ]
-            }),
-            Box::new(Button {
-                width: 100,
-                height: 100,
-                label: String::from("ok"),
-            }),
-        ]
-    };
+    let mut screen = Screen::new(40, 6).with_layout(Layout::Horizontal { gap: 2 });
+    screen.components = vec![
+        Box::new(
+            SelectBox::new(14, 5, &["yes", "no", "maybe"])
+                .on_change(|_, option| println!("selected: {}", option)),
+        ),
+        Box::new(Button::new(10, 3, "ok").on_click(|| println!("ok!"))),
+    ];
 
-    screen.run();
+    // Los componentes se dibujan en un lienzo de texto y se muestra en la terminal
+    screen.run().unwrap();
+
+    /* Los eventos pueden venir de cualquier sitio: aquí, de una lista ya
+     * escrita. Tab lleva el foco al SelectBox, las flechas cambian la opción,
+     * el clic pulsa el botón y Esc termina.
+     */
+    let events = vec![
+        Event::Key(Key::Tab),
+        Event::Key(Key::Down),
+        Event::Key(Key::Down),
+        Event::Click { x: 20, y: 1 },
+        Event::Key(Key::Esc),
+    ];
+    screen.run_with(events, &mut io::stdout().lock()).unwrap();
 }
 
 fn dispatches() {
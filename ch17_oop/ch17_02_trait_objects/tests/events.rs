@@ -0,0 +1,189 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use ch17_02_trait_objects::canvas::{Canvas, Rect, Size};
+use ch17_02_trait_objects::events::{Event, Key};
+use ch17_02_trait_objects::{Button, Draw, Handle, Screen, SelectBox};
+
+/* Los eventos se inyectan a mano con dispatch o run_with, y los callbacks
+ * apuntan lo que pasa en contadores compartidos.
+ */
+
+// Un texto que no acepta el foco ni eventos
+struct Label(&'static str);
+
+impl Draw for Label {
+    fn size(&self) -> Size {
+        Size {
+            width: self.0.len() as u32,
+            height: 1,
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, area: Rect) {
+        canvas.text(area.x, area.y, self.0);
+    }
+}
+
+impl Handle for Label {}
+
+fn counter() -> (Rc<Cell<u32>>, impl FnMut() + 'static) {
+    let count = Rc::new(Cell::new(0));
+    let shared = Rc::clone(&count);
+    (count, move || shared.set(shared.get() + 1))
+}
+
+// Etiqueta, SelectBox (filas 1 a 5) y botón (filas 6 a 8)
+fn screen(on_ok: impl FnMut() + 'static, changes: Rc<RefCell<Vec<String>>>) -> Screen {
+    let mut screen = Screen::new(20, 9);
+    screen.components = vec![
+        Box::new(Label("title")),
+        Box::new(
+            SelectBox::new(0, 0, &["yes", "no", "maybe"]).on_change(move |i, option| {
+                changes.borrow_mut().push(format!("{} {}", i, option));
+            }),
+        ),
+        Box::new(Button::new(10, 3, "ok").on_click(on_ok)),
+    ];
+    screen
+}
+
+#[test]
+fn tab_cycles_through_focusable_components() {
+    let mut screen = screen(|| {}, Rc::default());
+    assert_eq!(None, screen.focused());
+
+    assert!(screen.dispatch(Event::Key(Key::Tab)));
+    assert_eq!(Some(1), screen.focused());
+    screen.dispatch(Event::Key(Key::Tab));
+    assert_eq!(Some(2), screen.focused());
+    // Da la vuelta sin pasar por la etiqueta
+    screen.dispatch(Event::Key(Key::Tab));
+    assert_eq!(Some(1), screen.focused());
+
+    screen.dispatch(Event::Key(Key::BackTab));
+    assert_eq!(Some(2), screen.focused());
+    screen.dispatch(Event::Key(Key::BackTab));
+    assert_eq!(Some(1), screen.focused());
+
+    assert!(!screen.set_focus(0));
+    assert!(!screen.set_focus(7));
+    assert_eq!(Some(1), screen.focused());
+}
+
+#[test]
+fn keys_go_to_the_focused_component() {
+    let (clicks, on_ok) = counter();
+    let changes = Rc::default();
+    let mut screen = screen(on_ok, Rc::clone(&changes));
+
+    // Sin foco nadie recibe las teclas
+    assert!(!screen.dispatch(Event::Key(Key::Enter)));
+
+    screen.dispatch(Event::Key(Key::Tab));
+    assert!(screen.dispatch(Event::Key(Key::Down)));
+    assert!(screen.dispatch(Event::Key(Key::Down)));
+    // Ya está en la última
+    assert!(!screen.dispatch(Event::Key(Key::Down)));
+    assert!(screen.dispatch(Event::Key(Key::Up)));
+    assert!(!screen.dispatch(Event::Key(Key::Enter)));
+
+    screen.dispatch(Event::Key(Key::Tab));
+    assert!(screen.dispatch(Event::Key(Key::Enter)));
+    assert!(screen.dispatch(Event::Key(Key::Char(' '))));
+    assert!(!screen.dispatch(Event::Key(Key::Char('x'))));
+
+    assert_eq!(2, clicks.get());
+    assert_eq!(vec!["1 no", "2 maybe", "1 no"], *changes.borrow());
+}
+
+#[test]
+fn clicks_focus_and_reach_the_component_below() {
+    let (clicks, on_ok) = counter();
+    let changes = Rc::default();
+    let mut screen = screen(on_ok, Rc::clone(&changes));
+
+    // La fila 4 del SelectBox es su tercera opción
+    assert!(screen.dispatch(Event::Click { x: 3, y: 4 }));
+    assert_eq!(Some(1), screen.focused());
+    // En el borde no hay opción
+    assert!(!screen.dispatch(Event::Click { x: 3, y: 1 }));
+
+    assert!(screen.dispatch(Event::Click { x: 5, y: 7 }));
+    assert_eq!(Some(2), screen.focused());
+
+    // La etiqueta no toma el foco, y fuera de los componentes no pasa nada
+    assert!(!screen.dispatch(Event::Click { x: 0, y: 0 }));
+    assert!(!screen.dispatch(Event::Click { x: 15, y: 7 }));
+    assert_eq!(Some(2), screen.focused());
+
+    assert_eq!(1, clicks.get());
+    assert_eq!(vec!["2 maybe"], *changes.borrow());
+}
+
+#[test]
+fn select_only_reports_changes() {
+    let (changes, on_change) = counter();
+    let mut on_change = on_change;
+    let mut select_box = SelectBox::new(0, 0, &["a", "b"]).on_change(move |_, _| on_change());
+
+    assert!(!select_box.select(0));
+    assert!(!select_box.select(2));
+    assert!(select_box.select(1));
+    assert_eq!(1, select_box.selected());
+    assert_eq!(1, changes.get());
+}
+
+#[test]
+fn the_focused_component_has_a_double_border() {
+    let mut screen = screen(|| {}, Rc::default());
+    screen.dispatch(Event::Key(Key::BackTab));
+    insta::assert_snapshot!(screen.render_to_string());
+}
+
+#[test]
+fn run_with_redraws_after_each_event_until_esc() {
+    let (clicks, on_ok) = counter();
+    let mut screen = screen(on_ok, Rc::default());
+    let events = vec![
+        Event::Key(Key::Tab),
+        Event::Key(Key::Tab),
+        Event::Key(Key::Esc),
+        Event::Key(Key::Enter),
+    ];
+
+    let mut out = Vec::new();
+    screen.run_with(events, &mut out).unwrap();
+
+    // La pantalla inicial y una por evento antes de Esc
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(3, out.matches("\x1b[2J\x1b[H").count());
+    assert!(out.ends_with(&screen.render_to_string()));
+    assert_eq!(Some(2), screen.focused());
+    assert_eq!(0, clicks.get());
+}
+
+#[test]
+fn focus_is_dropped_when_its_component_goes_away() {
+    let (clicks, on_ok) = counter();
+    let mut screen = screen(on_ok, Rc::default());
+    screen.dispatch(Event::Key(Key::BackTab));
+    assert_eq!(Some(2), screen.focused());
+
+    // Quitan el botón con el foco: las teclas ya no van a ningún sitio
+    screen.components.pop();
+    assert_eq!(None, screen.focused());
+    assert!(!screen.render_to_string().contains('╔'));
+    assert!(!screen.dispatch(Event::Key(Key::Enter)));
+
+    // Y en su lugar ponen algo que no acepta el foco
+    screen.set_focus(1);
+    screen.components[1] = Box::new(Label("gone"));
+    assert!(!screen.dispatch(Event::Key(Key::Down)));
+    assert_eq!(None, screen.focused());
+
+    screen.components.push(Box::new(Button::new(10, 3, "new")));
+    screen.dispatch(Event::Key(Key::Tab));
+    assert_eq!(Some(2), screen.focused());
+    assert_eq!(0, clicks.get());
+}
@@ -0,0 +1,85 @@
+use ch17_02_trait_objects::canvas::Rect;
+use ch17_02_trait_objects::{Button, Layout, Screen, SelectBox};
+
+/* Las pantallas dibujadas se comparan con las de tests/snapshots. Para
+ * aceptar un cambio a propósito: INSTA_UPDATE=always cargo test --test render
+ */
+
+fn select_box() -> SelectBox {
+    let mut select_box = SelectBox::new(0, 0, &["yes", "no", "maybe"]);
+    select_box.select(1);
+    select_box
+}
+
+fn button(label: &str) -> Button {
+    Button::new(10, 3, label)
+}
+
+fn screen(width: u32, height: u32, layout: Layout) -> Screen {
+    let mut screen = Screen::new(width, height).with_layout(layout);
+    screen.components = vec![
+        Box::new(select_box()),
+        Box::new(button("ok")),
+        Box::new(button("cancel")),
+    ];
+    screen
+}
+
+#[test]
+fn vertical_layout() {
+    let screen = screen(20, 12, Layout::Vertical { gap: 0 });
+    assert_eq!(
+        vec![
+            Rect::new(0, 0, 13, 5),
+            Rect::new(0, 5, 10, 3),
+            Rect::new(0, 8, 10, 3)
+        ],
+        screen.layout()
+    );
+    insta::assert_snapshot!(screen.render_to_string());
+}
+
+#[test]
+fn horizontal_layout_with_gaps() {
+    let screen = screen(40, 5, Layout::Horizontal { gap: 1 });
+    assert_eq!(
+        vec![
+            Rect::new(0, 0, 13, 5),
+            Rect::new(14, 0, 10, 3),
+            Rect::new(25, 0, 10, 3)
+        ],
+        screen.layout()
+    );
+    insta::assert_snapshot!(screen.render_to_string());
+}
+
+#[test]
+fn components_are_cut_at_the_edge() {
+    let screen = screen(16, 7, Layout::Vertical { gap: 1 });
+    insta::assert_snapshot!(screen.render_to_string());
+}
+
+#[test]
+fn sizes_fit_the_contents() {
+    let mut screen = Screen::new(30, 8);
+    screen.components = vec![
+        Box::new(Button::new(0, 5, "a long label")),
+        Box::new(SelectBox::new(20, 0, &[])),
+    ];
+
+    assert_eq!(
+        vec![Rect::new(0, 0, 16, 5), Rect::new(0, 5, 20, 2)],
+        screen.layout()
+    );
+    insta::assert_snapshot!(screen.render_to_string());
+}
+
+#[test]
+fn terminal_output_clears_the_screen() {
+    let screen = screen(12, 3, Layout::Horizontal { gap: 0 });
+    let mut out = Vec::new();
+    screen.draw_to(&mut out).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(format!("\x1b[2J\x1b[H{}", screen.render_to_string()), out);
+}
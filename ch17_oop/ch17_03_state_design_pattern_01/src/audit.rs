@@ -0,0 +1,152 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{EventRecord, StorageError};
+use crate::{Event, TransitionListener};
+
+/* El registro de auditoría solo crece: cada transición se añade al final del
+ * archivo como una línea de JSON y nunca se reescribe. Así, aunque el programa
+ * se cierre a medias, lo que ya se escribió sigue ahí.
+ *
+ * Varios posts pueden compartir el mismo archivo, así que cada línea lleva el
+ * identificador del post al que pertenece.
+ *
+ * Como listener no puede devolver errores, los fallos al escribir se guardan
+ * para poder consultarlos después. Para no perder el acceso a ellos al pasar
+ * el log al post, se le puede pasar un Rc<RefCell<AuditLog>> y quedarse con
+ * otra copia del Rc.
+ */
+
+/// A transition read from the log, with the post it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub post: String,
+    pub event: Event,
+}
+
+// Una línea del archivo: el evento tal cual se guarda, con el post delante
+#[derive(Serialize, Deserialize)]
+struct AuditRecord {
+    post: String,
+    #[serde(flatten)]
+    event: EventRecord,
+}
+
+/// An append-only log of the transitions of posts, one JSON line per event.
+///
+/// # Examples
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use ch17_03_state_design_pattern_01::audit::AuditLog;
+/// use ch17_03_state_design_pattern_01::permissions::User;
+/// use ch17_03_state_design_pattern_01::{Post, Status};
+///
+/// let path = std::env::temp_dir().join("blog_audit_doc_example.log");
+/// # let _ = std::fs::remove_file(&path);
+/// let log = Rc::new(RefCell::new(AuditLog::open(&path, "hello-world").unwrap()));
+/// let mut post = Post::with_author("ferris");
+/// post.add_listener(Rc::clone(&log));
+///
+/// post.request_review(&User::author("ferris")).unwrap();
+/// post.approve(&User::reviewer("bea")).unwrap();
+/// assert!(log.borrow().errors().is_empty());
+///
+/// let events = AuditLog::read_post(&path, "hello-world").unwrap();
+/// assert_eq!("bea", events[1].actor);
+///
+/// let replayed = Post::replay("ferris", &events).unwrap();
+/// assert_eq!(Status::PendingReview, replayed.status());
+/// assert_eq!(1, replayed.approvals());
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: File,
+    post: String,
+    errors: Vec<StorageError>,
+}
+
+impl AuditLog {
+    /// Opens the log to add the events of `post` after the ones it already
+    /// has, creating it if needed.
+    pub fn open<P: AsRef<Path>>(path: P, post: &str) -> Result<AuditLog, StorageError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(AuditLog {
+            path,
+            file,
+            post: String::from(post),
+            errors: Vec::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The post whose events are written.
+    pub fn post(&self) -> &str {
+        &self.post
+    }
+
+    /// Writes the event at the end of the log.
+    pub fn append(&mut self, event: &Event) -> Result<(), StorageError> {
+        let record = AuditRecord {
+            post: self.post.clone(),
+            event: EventRecord::from_event(event),
+        };
+        let line = serde_json::to_string(&record)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// The errors found while writing events received as a listener.
+    pub fn errors(&self) -> &[StorageError] {
+        &self.errors
+    }
+
+    /// Reads every event in the log, of every post, oldest first. A log that
+    /// doesn't exist is empty.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<AuditEntry>, StorageError> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let record: AuditRecord = serde_json::from_str(line)?;
+                Ok(AuditEntry {
+                    post: record.post,
+                    event: record.event.to_event()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads the events of a single post, oldest first, ready to
+    /// [`Post::replay`](crate::Post::replay).
+    pub fn read_post<P: AsRef<Path>>(path: P, post: &str) -> Result<Vec<Event>, StorageError> {
+        Ok(AuditLog::read(path)?
+            .into_iter()
+            .filter(|entry| entry.post == post)
+            .map(|entry| entry.event)
+            .collect())
+    }
+}
+
+impl TransitionListener for AuditLog {
+    fn on_transition(&mut self, event: &Event) {
+        if let Err(e) = self.append(event) {
+            self.errors.push(e);
+        }
+    }
+}
@@ -1,41 +1,598 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+
+pub mod audit;
+pub mod permissions;
+pub mod revisions;
+pub mod site;
+pub mod storage;
+
+use permissions::{PermissionError, User};
+use revisions::{DiffLine, Revision};
+
+/// Who is recorded as doing the transitions that happen on their own, like
+/// publishing a scheduled post.
+pub const SYSTEM: &str = "system";
+
+/// Approvals a post needs before it's published.
+pub const REQUIRED_APPROVALS: u32 = 2;
+
+/// A blog post that goes through an editorial workflow:
+///
+/// ```text
+/// Draft ──request_review──> PendingReview ──approve × 2──> Published ──archive──> Archived
+///                                 │                            ^
+///                      schedule + approve × 2                  │
+///                                 └──────> Scheduled ──publish_due
+/// ```
+///
+/// `reject` sends a post that is pending review or scheduled back to draft,
+/// and an admin can `force_publish` it without the approvals. Who can do each
+/// transition is described in [`permissions`].
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_01::permissions::User;
+/// use ch17_03_state_design_pattern_01::{Post, PostError, Action, Status};
+///
+/// let ana = User::author("ana");
+/// let mut post = Post::with_author(ana.name());
+/// post.add_text(&ana, "I ate salad for lunch today").unwrap();
+///
+/// post.request_review(&ana).unwrap();
+/// assert_eq!(
+///     Err(PostError::InvalidTransition { action: Action::AddText, from: Status::PendingReview }),
+///     post.add_text(&ana, " and it was great")
+/// );
+///
+/// post.approve(&User::reviewer("bea")).unwrap();
+/// assert_eq!("", post.content());
+/// post.approve(&User::reviewer("carla")).unwrap();
+/// assert_eq!("I ate salad for lunch today", post.content());
+/// assert_eq!("carla", post.history()[2].actor);
+/// ```
 pub struct Post {
     state: Option<Box<dyn State>>,
-    content: String,
+    // Nunca está vacío: la revisión 1 es el post recién creado
+    revisions: Vec<Revision>,
+    approved_revision: Option<usize>,
+    author: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    history: Vec<Event>,
+    listeners: Vec<Box<dyn TransitionListener>>,
 }
 
 impl Post {
     pub fn new() -> Post {
+        Post::with_author("")
+    }
+
+    pub fn with_author(author: &str) -> Post {
+        let now = Utc::now();
         Post {
             state: Some(Box::new(Draft {})),
-            content: String::new(),
+            revisions: vec![Revision {
+                number: 1,
+                content: String::new(),
+                at: now,
+            }],
+            approved_revision: None,
+            author: String::from(author),
+            created_at: now,
+            updated_at: now,
+            history: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Rebuilds a post by applying the events of its history in order, for
+    /// example from an [`audit::AuditLog`]. The text isn't part of the
+    /// history, so the post has none.
+    ///
+    /// Fails if an event doesn't start from the status the post is in, or
+    /// doesn't lead to the status it says.
+    pub fn replay(author: &str, events: &[Event]) -> Result<Post, PostError> {
+        let mut post = Post::with_author(author);
+        if let Some(first) = events.first() {
+            post.created_at = first.at;
+            post.updated_at = first.at;
+        }
+
+        for (index, event) in events.iter().enumerate() {
+            let found = post.status();
+            if found != event.from {
+                return Err(PostError::ReplayMismatch {
+                    index,
+                    expected: event.from,
+                    found,
+                });
+            }
+            if event.action == Action::Schedule && event.publish_at.is_none() {
+                return Err(PostError::MissingDate { index });
+            }
+
+            post.apply(event.action, &event.actor, event.at, event.publish_at)?;
+
+            let found = post.status();
+            if found != event.to {
+                return Err(PostError::ReplayMismatch {
+                    index,
+                    expected: event.to,
+                    found,
+                });
+            }
         }
+
+        Ok(post)
     }
 
-    pub fn add_text(&mut self, text: &str) {
-        self.content.push_str(text);
+    /// Calls the listener after every transition of the post.
+    pub fn add_listener<L: TransitionListener + 'static>(&mut self, listener: L) {
+        self.listeners.push(Box::new(listener));
     }
 
+    /// Adds text to the post, as a new revision. Only drafts can be edited,
+    /// by their author or an admin.
+    pub fn add_text(&mut self, user: &User, text: &str) -> Result<(), PostError> {
+        self.check_editable(Action::AddText, user)?;
+
+        let content = format!("{}{}", self.draft(), text);
+        self.add_revision(content);
+        Ok(())
+    }
+
+    /// Goes back to the text of an earlier revision. The revisions in
+    /// between aren't lost: the old text becomes a new revision.
+    ///
+    /// # Examples
+    /// ```
+    /// use ch17_03_state_design_pattern_01::permissions::User;
+    /// use ch17_03_state_design_pattern_01::Post;
+    ///
+    /// let ana = User::author("ana");
+    /// let mut post = Post::with_author(ana.name());
+    /// post.add_text(&ana, "Hello\n").unwrap();
+    /// post.add_text(&ana, "world\n").unwrap();
+    /// post.revert(&ana, 2).unwrap();
+    ///
+    /// assert_eq!("Hello\n", post.draft());
+    /// assert_eq!(4, post.revisions().len());
+    /// let changes: Vec<String> = post.diff(3, 4).unwrap().iter().map(|l| l.to_string()).collect();
+    /// assert_eq!(vec!["  Hello", "- world"], changes);
+    /// ```
+    pub fn revert(&mut self, user: &User, number: usize) -> Result<(), PostError> {
+        self.check_editable(Action::Revert, user)?;
+
+        let content = self.revision(number)?.content.clone();
+        self.add_revision(content);
+        Ok(())
+    }
+
+    /// The text of the post, or an empty string if it isn't published. Once
+    /// published, it's the text of the approved revision.
     pub fn content(&self) -> &str {
-        self.state.as_ref().unwrap().content(self)
+        self.state().content(self)
+    }
+
+    /// The text of the latest revision, whatever the status of the post.
+    pub fn draft(&self) -> &str {
+        &self.latest().content
+    }
+
+    /// Every revision of the text, oldest first.
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    pub fn revision(&self, number: usize) -> Result<&Revision, PostError> {
+        number
+            .checked_sub(1)
+            .and_then(|index| self.revisions.get(index))
+            .ok_or(PostError::UnknownRevision(number))
+    }
+
+    /// The revision that got the approvals, while the post is scheduled or
+    /// published.
+    pub fn approved_revision(&self) -> Option<usize> {
+        self.approved_revision
+    }
+
+    /// What changed, line by line, from one revision to another.
+    pub fn diff(&self, from: usize, to: usize) -> Result<Vec<DiffLine>, PostError> {
+        let from = self.revision(from)?;
+        let to = self.revision(to)?;
+        Ok(revisions::diff(&from.content, &to.content))
+    }
+
+    pub fn status(&self) -> Status {
+        self.state().status()
+    }
+
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Last time the text or the status changed.
+    pub fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    /// Every transition the post went through, oldest first.
+    pub fn history(&self) -> &[Event] {
+        &self.history
+    }
+
+    /// Approvals received since the post was last sent to review.
+    pub fn approvals(&self) -> u32 {
+        self.state().approvals()
+    }
+
+    /// Who approved the post since it was last sent to review, while it's
+    /// still in review.
+    pub fn approvers(&self) -> &[String] {
+        self.state().approvers()
+    }
+
+    /// When a scheduled post will be published.
+    pub fn publish_at(&self) -> Option<DateTime<Utc>> {
+        self.state().publish_at()
+    }
+
+    pub fn request_review(&mut self, user: &User) -> Result<(), PostError> {
+        self.act(Action::RequestReview, user, None)
+    }
+
+    /// Approves a post in review. With the second approval it's published,
+    /// or scheduled if a publication date was set. Each reviewer can only
+    /// approve it once.
+    pub fn approve(&mut self, user: &User) -> Result<(), PostError> {
+        self.act(Action::Approve, user, None)
+    }
+
+    /// Sends a post in review, or scheduled, back to draft. Its approvals are lost.
+    pub fn reject(&mut self, user: &User) -> Result<(), PostError> {
+        self.act(Action::Reject, user, None)
     }
 
-    pub fn request_review(&mut self) {
-        if let Some(state) = self.state.take() {
-            self.state = Some(state.request_review());
+    /// Sets the date when a post in review will be published once approved.
+    pub fn schedule(&mut self, user: &User, publish_at: DateTime<Utc>) -> Result<(), PostError> {
+        self.act(Action::Schedule, user, Some(publish_at))
+    }
+
+    /// Publishes a scheduled post if its date has arrived. Returns whether it
+    /// was published. The transition is recorded as done by [`SYSTEM`].
+    pub fn publish_due(&mut self, now: DateTime<Utc>) -> Result<bool, PostError> {
+        self.apply(Action::Publish, SYSTEM, now, None)?;
+        Ok(self.status() == Status::Published)
+    }
+
+    /// Publishes a post in review, or scheduled, right away, whatever its
+    /// approvals.
+    pub fn force_publish(&mut self, user: &User) -> Result<(), PostError> {
+        self.act(Action::ForcePublish, user, None)
+    }
+
+    /// Takes a published post down.
+    pub fn archive(&mut self, user: &User) -> Result<(), PostError> {
+        self.act(Action::Archive, user, None)
+    }
+
+    // Las acciones de los usuarios pasan primero por los permisos
+    fn act(
+        &mut self,
+        action: Action,
+        user: &User,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<(), PostError> {
+        permissions::check(action, user, &self.author)?;
+        self.apply(action, user.name(), Utc::now(), publish_at)
+    }
+
+    fn state(&self) -> &dyn State {
+        self.state.as_deref().unwrap()
+    }
+
+    fn latest(&self) -> &Revision {
+        self.revisions.last().unwrap()
+    }
+
+    fn approved_content(&self) -> &str {
+        match self.approved_revision {
+            Some(number) => &self.revisions[number - 1].content,
+            None => "",
         }
     }
-    
-    pub fn approve(&mut self) {
-        if let Some(state) = self.state.take() {
-            self.state = Some(state.approve());
+
+    // Como en act, los permisos van antes que el estado
+    fn check_editable(&self, action: Action, user: &User) -> Result<(), PostError> {
+        permissions::check(action, user, &self.author)?;
+        if self.state().can_edit() {
+            Ok(())
+        } else {
+            Err(PostError::InvalidTransition {
+                action,
+                from: self.status(),
+            })
         }
     }
+
+    fn add_revision(&mut self, content: String) {
+        let at = Utc::now();
+        self.revisions.push(Revision {
+            number: self.revisions.len() + 1,
+            content,
+            at,
+        });
+        self.updated_at = at;
+    }
+
+    /* El estado se saca del Option para poder consumirlo (self: Box<Self>).
+     * Si la transición no es válida, el estado lo devuelve en el Err y se
+     * vuelve a guardar tal cual. Si es válida, se apunta en el historial y se
+     * avisa a los listeners (salvo publish_due antes de tiempo, que no
+     * cambia nada).
+     */
+    fn apply(
+        &mut self,
+        action: Action,
+        actor: &str,
+        at: DateTime<Utc>,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<(), PostError> {
+        // También al reproducir: dos aprobaciones de la misma persona no valen
+        if action == Action::Approve && self.approvers().iter().any(|name| name == actor) {
+            return Err(PermissionError::AlreadyApproved.into());
+        }
+
+        let state = self.state.take().unwrap();
+        let from = state.status();
+
+        let next = match (action, publish_at) {
+            (Action::RequestReview, _) => state.request_review(),
+            (Action::Approve, _) => state.approve(actor),
+            (Action::Reject, _) => state.reject(),
+            (Action::Schedule, Some(publish_at)) => state.schedule(publish_at),
+            (Action::Publish, _) => state.publish_due(at),
+            (Action::ForcePublish, _) => state.force_publish(),
+            (Action::Archive, _) => state.archive(),
+            (Action::Schedule, None) | (Action::AddText | Action::Revert, _) => Err(state),
+        };
+
+        match next {
+            Ok(state) => {
+                let to = state.status();
+                self.state = Some(state);
+
+                // El texto aprobado queda fijado hasta que vuelva a borrador
+                match to {
+                    Status::Draft => self.approved_revision = None,
+                    Status::Scheduled | Status::Published if self.approved_revision.is_none() => {
+                        self.approved_revision = Some(self.latest().number)
+                    }
+                    _ => {}
+                }
+
+                if action != Action::Publish || to != from {
+                    let event = Event {
+                        action,
+                        actor: String::from(actor),
+                        from,
+                        to,
+                        at,
+                        publish_at,
+                    };
+                    for listener in self.listeners.iter_mut() {
+                        listener.on_transition(&event);
+                    }
+                    self.history.push(event);
+                    self.updated_at = at;
+                }
+                Ok(())
+            }
+            Err(state) => {
+                self.state = Some(state);
+                Err(PostError::InvalidTransition { action, from })
+            }
+        }
+    }
+}
+
+impl Default for Post {
+    fn default() -> Post {
+        Post::new()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    Draft,
+    PendingReview,
+    Scheduled,
+    Published,
+    Archived,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Status::Draft => "draft",
+            Status::PendingReview => "pending review",
+            Status::Scheduled => "scheduled",
+            Status::Published => "published",
+            Status::Archived => "archived",
+        };
+        write!(f, "{}", name)
+    }
 }
 
+/// Everything that can be done to a post.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    AddText,
+    RequestReview,
+    Approve,
+    Reject,
+    Schedule,
+    Publish,
+    ForcePublish,
+    Archive,
+    Revert,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Action::AddText => "add text to",
+            Action::RequestReview => "request review of",
+            Action::Approve => "approve",
+            Action::Reject => "reject",
+            Action::Schedule => "schedule",
+            Action::Publish => "publish",
+            Action::ForcePublish => "force-publish",
+            Action::Archive => "archive",
+            Action::Revert => "revert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A transition in the history of a post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub action: Action,
+    /// Who did it.
+    pub actor: String,
+    pub from: Status,
+    pub to: Status,
+    pub at: DateTime<Utc>,
+    /// The publication date given, only when scheduling.
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// Gets notified of every transition of a post.
+///
+/// # Examples
+/// ```
+/// use std::cell::RefCell;
+/// use std::rc::Rc;
+/// use ch17_03_state_design_pattern_01::permissions::User;
+/// use ch17_03_state_design_pattern_01::{Event, Post, Status};
+///
+/// let seen = Rc::new(RefCell::new(Vec::new()));
+/// let mut post = Post::with_author("ana");
+///
+/// let listener = Rc::clone(&seen);
+/// post.add_listener(move |event: &Event| listener.borrow_mut().push(event.to));
+///
+/// post.request_review(&User::author("ana")).unwrap();
+/// post.reject(&User::reviewer("bea")).unwrap();
+/// assert_eq!(vec![Status::PendingReview, Status::Draft], *seen.borrow());
+/// ```
+pub trait TransitionListener {
+    fn on_transition(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event)> TransitionListener for F {
+    fn on_transition(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+/// A listener shared with the caller, who can still look at it after adding
+/// it to the post.
+impl<L: TransitionListener> TransitionListener for Rc<RefCell<L>> {
+    fn on_transition(&mut self, event: &Event) {
+        self.borrow_mut().on_transition(event)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostError {
+    /// The action can't be done to a post in that status.
+    InvalidTransition { action: Action, from: Status },
+    /// While replaying, event `index` expected the post in a different status.
+    ReplayMismatch {
+        index: usize,
+        expected: Status,
+        found: Status,
+    },
+    /// While replaying, event `index` schedules the post without a date.
+    MissingDate { index: usize },
+    /// The user isn't allowed to do that.
+    PermissionDenied(PermissionError),
+    UnknownRevision(usize),
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PostError::InvalidTransition { action, from } => {
+                write!(f, "can't {} a post that is {}", action, from)
+            }
+            PostError::ReplayMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "event {} expected a post that is {}, but it is {}",
+                index, expected, found
+            ),
+            PostError::MissingDate { index } => {
+                write!(f, "event {} schedules the post without a date", index)
+            }
+            PostError::PermissionDenied(e) => write!(f, "permission denied: {}", e),
+            PostError::UnknownRevision(number) => write!(f, "there's no revision {}", number),
+        }
+    }
+}
+
+impl std::error::Error for PostError {}
+
+impl From<PermissionError> for PostError {
+    fn from(e: PermissionError) -> PostError {
+        PostError::PermissionDenied(e)
+    }
+}
+
+// Ok con el nuevo estado, o Err con el mismo estado si la transición no es válida
+type Transition = Result<Box<dyn State>, Box<dyn State>>;
+
 trait State {
-    fn request_review(self: Box<Self>) -> Box<dyn State>;
-    fn approve(self: Box<Self>) -> Box<dyn State>;
-    fn content<'a>(&self, post: &'a Post) -> &'a str {
+    fn status(&self) -> Status;
+    fn request_review(self: Box<Self>) -> Transition;
+    fn approve(self: Box<Self>, approver: &str) -> Transition;
+    fn reject(self: Box<Self>) -> Transition;
+    fn schedule(self: Box<Self>, publish_at: DateTime<Utc>) -> Transition;
+    fn publish_due(self: Box<Self>, now: DateTime<Utc>) -> Transition;
+    fn force_publish(self: Box<Self>) -> Transition;
+    fn archive(self: Box<Self>) -> Transition;
+
+    fn can_edit(&self) -> bool {
+        false
+    }
+
+    fn approvals(&self) -> u32 {
+        0
+    }
+
+    fn approvers(&self) -> &[String] {
+        &[]
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn content<'a>(&self, _post: &'a Post) -> &'a str {
         ""
     }
 }
@@ -43,39 +600,697 @@ trait State {
 struct Draft {}
 
 impl State for Draft {
-    fn request_review(self: Box<Self>) -> Box<dyn State> {
-        Box::new(PendingReview {})
+    fn status(&self) -> Status {
+        Status::Draft
+    }
+
+    fn request_review(self: Box<Self>) -> Transition {
+        Ok(Box::new(PendingReview {
+            approvers: Vec::new(),
+            publish_at: None,
+        }))
+    }
+
+    fn approve(self: Box<Self>, _approver: &str) -> Transition {
+        Err(self)
+    }
+
+    fn reject(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn schedule(self: Box<Self>, _publish_at: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn publish_due(self: Box<Self>, _now: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn force_publish(self: Box<Self>) -> Transition {
+        Err(self)
     }
 
-    fn approve(self: Box<Self>) -> Box<dyn State> {
-        self
+    fn archive(self: Box<Self>) -> Transition {
+        Err(self)
     }
-}   
 
-struct PendingReview {}
+    fn can_edit(&self) -> bool {
+        true
+    }
+}
+
+struct PendingReview {
+    approvers: Vec<String>,
+    publish_at: Option<DateTime<Utc>>,
+}
 
 impl State for PendingReview {
-    fn request_review(self: Box<Self>) -> Box<dyn State> {
-        self
-    }    
-    
-    fn approve(self: Box<Self>) -> Box<dyn State> {
-        Box::new(Published {})
+    fn status(&self) -> Status {
+        Status::PendingReview
+    }
+
+    fn request_review(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approve(mut self: Box<Self>, approver: &str) -> Transition {
+        self.approvers.push(String::from(approver));
+        if self.approvals() < REQUIRED_APPROVALS {
+            return Ok(self);
+        }
+
+        match self.publish_at {
+            Some(publish_at) => Ok(Box::new(Scheduled { publish_at })),
+            None => Ok(Box::new(Published {})),
+        }
+    }
+
+    fn reject(self: Box<Self>) -> Transition {
+        Ok(Box::new(Draft {}))
+    }
+
+    fn schedule(self: Box<Self>, publish_at: DateTime<Utc>) -> Transition {
+        Ok(Box::new(PendingReview {
+            publish_at: Some(publish_at),
+            ..*self
+        }))
+    }
+
+    fn publish_due(self: Box<Self>, _now: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn force_publish(self: Box<Self>) -> Transition {
+        Ok(Box::new(Published {}))
+    }
+
+    fn archive(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approvals(&self) -> u32 {
+        self.approvers.len() as u32
+    }
+
+    fn approvers(&self) -> &[String] {
+        &self.approvers
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        self.publish_at
+    }
+}
+
+struct Scheduled {
+    publish_at: DateTime<Utc>,
+}
+
+impl State for Scheduled {
+    fn status(&self) -> Status {
+        Status::Scheduled
+    }
+
+    fn request_review(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approve(self: Box<Self>, _approver: &str) -> Transition {
+        Err(self)
+    }
+
+    fn reject(self: Box<Self>) -> Transition {
+        Ok(Box::new(Draft {}))
+    }
+
+    fn schedule(self: Box<Self>, _publish_at: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    // Llamarlo antes de tiempo no es un error: sigue programado
+    fn publish_due(self: Box<Self>, now: DateTime<Utc>) -> Transition {
+        if now >= self.publish_at {
+            Ok(Box::new(Published {}))
+        } else {
+            Ok(self)
+        }
+    }
+
+    fn force_publish(self: Box<Self>) -> Transition {
+        Ok(Box::new(Published {}))
+    }
+
+    fn archive(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approvals(&self) -> u32 {
+        REQUIRED_APPROVALS
+    }
+
+    fn publish_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.publish_at)
     }
 }
 
 struct Published {}
 
 impl State for Published {
-    fn request_review(self: Box<Self>) -> Box<dyn State> {
-        self
-    }    
-    
-    fn approve(self: Box<Self>) -> Box<dyn State> {
-        self
+    fn status(&self) -> Status {
+        Status::Published
+    }
+
+    fn request_review(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approve(self: Box<Self>, _approver: &str) -> Transition {
+        Err(self)
+    }
+
+    fn reject(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn schedule(self: Box<Self>, _publish_at: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn publish_due(self: Box<Self>, _now: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn force_publish(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn archive(self: Box<Self>) -> Transition {
+        Ok(Box::new(Archived {}))
+    }
+
+    fn approvals(&self) -> u32 {
+        REQUIRED_APPROVALS
     }
 
     fn content<'a>(&self, post: &'a Post) -> &'a str {
-        &post.content
+        post.approved_content()
+    }
+}
+
+struct Archived {}
+
+impl State for Archived {
+    fn status(&self) -> Status {
+        Status::Archived
+    }
+
+    fn request_review(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn approve(self: Box<Self>, _approver: &str) -> Transition {
+        Err(self)
+    }
+
+    fn reject(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn schedule(self: Box<Self>, _publish_at: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn publish_due(self: Box<Self>, _now: DateTime<Utc>) -> Transition {
+        Err(self)
+    }
+
+    fn force_publish(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+
+    fn archive(self: Box<Self>) -> Transition {
+        Err(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::permissions::Role;
+    use chrono::TimeZone;
+
+    fn ana() -> User {
+        User::author("ana")
+    }
+
+    fn bea() -> User {
+        User::reviewer("bea")
+    }
+
+    fn carla() -> User {
+        User::reviewer("carla")
+    }
+
+    fn dani() -> User {
+        User::admin("dani")
+    }
+
+    fn reviewed(text: &str) -> Post {
+        let mut post = Post::with_author("ana");
+        post.add_text(&ana(), text).unwrap();
+        post.request_review(&ana()).unwrap();
+        post
+    }
+
+    fn invalid(action: Action, from: Status) -> Result<(), PostError> {
+        Err(PostError::InvalidTransition { action, from })
+    }
+
+    fn denied(error: PermissionError) -> Result<(), PostError> {
+        Err(PostError::PermissionDenied(error))
+    }
+
+    #[test]
+    fn needs_two_approvals() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        assert_eq!(Status::PendingReview, post.status());
+        assert_eq!(1, post.approvals());
+        assert_eq!("", post.content());
+
+        post.approve(&carla()).unwrap();
+        assert_eq!(Status::Published, post.status());
+        assert_eq!("Hello", post.content());
+    }
+
+    #[test]
+    fn each_reviewer_approves_once() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        assert_eq!(
+            denied(PermissionError::AlreadyApproved),
+            post.approve(&bea())
+        );
+        assert_eq!(Status::PendingReview, post.status());
+        assert_eq!(vec!["bea"], post.approvers());
+        assert_eq!(2, post.history().len());
+
+        post.approve(&carla()).unwrap();
+        assert_eq!(Status::Published, post.status());
+        assert!(post.approvers().is_empty());
+
+        // Una historia con la misma aprobación dos veces no se puede reproducir
+        let mut events = reviewed("Hello").history().to_vec();
+        let mut approval = events[0].clone();
+        approval.action = Action::Approve;
+        approval.actor = String::from("bea");
+        approval.from = Status::PendingReview;
+        events.push(approval.clone());
+        events.push(approval);
+        assert_eq!(
+            denied(PermissionError::AlreadyApproved),
+            Post::replay("ana", &events).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn reject_goes_back_to_draft() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        post.reject(&bea()).unwrap();
+        assert_eq!(Status::Draft, post.status());
+        assert_eq!(0, post.approvals());
+
+        post.add_text(&ana(), ", world").unwrap();
+        post.request_review(&ana()).unwrap();
+        post.approve(&bea()).unwrap();
+        // Las aprobaciones anteriores no cuentan
+        assert_eq!(Status::PendingReview, post.status());
+        post.approve(&carla()).unwrap();
+        assert_eq!("Hello, world", post.content());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn only_drafts_can_be_edited() {
+        let mut post = reviewed("Hello");
+        assert_eq!(
+            invalid(Action::AddText, Status::PendingReview),
+            post.add_text(&ana(), "!")
+        );
+
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        assert_eq!(
+            invalid(Action::AddText, Status::Published),
+            post.add_text(&ana(), "!")
+        );
+        assert_eq!("Hello", post.content());
+    }
+
+    #[test]
+    fn scheduled_posts_are_published_on_time() {
+        let monday = Utc.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+        let tuesday = Utc.with_ymd_and_hms(2024, 5, 7, 9, 0, 0).unwrap();
+
+        let mut post = reviewed("Hello");
+        post.schedule(&ana(), tuesday).unwrap();
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        assert_eq!(Status::Scheduled, post.status());
+        assert_eq!(Some(tuesday), post.publish_at());
+
+        assert_eq!(Ok(false), post.publish_due(monday));
+        assert_eq!("", post.content());
+        assert_eq!(Ok(true), post.publish_due(tuesday));
+        assert_eq!("Hello", post.content());
+    }
+
+    #[test]
+    fn scheduled_posts_can_be_rejected() {
+        let tuesday = Utc.with_ymd_and_hms(2024, 5, 7, 9, 0, 0).unwrap();
+        let mut post = reviewed("Hello");
+        post.schedule(&ana(), tuesday).unwrap();
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+
+        post.reject(&bea()).unwrap();
+        assert_eq!(Status::Draft, post.status());
+        assert_eq!(None, post.publish_at());
+    }
+
+    #[test]
+    fn archived_posts_are_hidden() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        post.archive(&dani()).unwrap();
+
+        assert_eq!(Status::Archived, post.status());
+        assert_eq!("", post.content());
+        assert_eq!(invalid(Action::Archive, Status::Archived), post.archive(&dani()));
+    }
+
+    #[test]
+    fn invalid_transitions_keep_the_state() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 9, 0, 0).unwrap();
+        let mut post = Post::with_author("ana");
+
+        assert_eq!(invalid(Action::Approve, Status::Draft), post.approve(&bea()));
+        assert_eq!(invalid(Action::Reject, Status::Draft), post.reject(&bea()));
+        assert_eq!(invalid(Action::Schedule, Status::Draft), post.schedule(&ana(), now));
+        assert_eq!(
+            Err(PostError::InvalidTransition {
+                action: Action::Publish,
+                from: Status::Draft
+            }),
+            post.publish_due(now)
+        );
+        assert_eq!(invalid(Action::Archive, Status::Draft), post.archive(&dani()));
+        assert_eq!(Status::Draft, post.status());
+
+        post.request_review(&ana()).unwrap();
+        assert_eq!(
+            invalid(Action::RequestReview, Status::PendingReview),
+            post.request_review(&ana())
+        );
+        assert_eq!(Status::PendingReview, post.status());
+    }
+
+    #[test]
+    fn listeners_see_every_transition() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let listener = Rc::clone(&seen);
+        let mut post = Post::with_author("ana");
+        post.add_listener(move |event: &Event| listener.borrow_mut().push(event.clone()));
+
+        post.request_review(&ana()).unwrap();
+        post.approve(&bea()).unwrap();
+        // Las transiciones que fallan no se notifican
+        post.archive(&bea()).unwrap_err();
+        post.reject(&carla()).unwrap();
+
+        assert_eq!(post.history(), &seen.borrow()[..]);
+        let actors: Vec<&str> = post.history().iter().map(|e| e.actor.as_str()).collect();
+        assert_eq!(vec!["ana", "bea", "carla"], actors);
+    }
+
+    #[test]
+    fn replay_rebuilds_the_state() {
+        let tuesday = Utc.with_ymd_and_hms(2024, 5, 7, 9, 0, 0).unwrap();
+        let mut post = reviewed("Hello");
+        post.schedule(&ana(), tuesday).unwrap();
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        post.publish_due(tuesday).unwrap();
+
+        let replayed = Post::replay("ana", post.history()).unwrap();
+        assert_eq!(Status::Published, replayed.status());
+        assert_eq!(post.history(), replayed.history());
+        assert_eq!(SYSTEM, replayed.history()[4].actor);
+        assert_eq!(tuesday, replayed.updated_at());
+    }
+
+    #[test]
+    fn replay_refuses_impossible_histories() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+
+        let mut events = post.history().to_vec();
+        events.remove(0);
+        assert_eq!(
+            Err(PostError::ReplayMismatch {
+                index: 0,
+                expected: Status::PendingReview,
+                found: Status::Draft
+            }),
+            Post::replay("ana", &events).map(|post| post.status())
+        );
+
+        let mut events = post.history().to_vec();
+        events[1].to = Status::Published;
+        assert_eq!(
+            Err(PostError::ReplayMismatch {
+                index: 1,
+                expected: Status::Published,
+                found: Status::PendingReview
+            }),
+            Post::replay("ana", &events).map(|post| post.status())
+        );
+
+        let mut events = post.history().to_vec();
+        events[1].action = Action::Schedule;
+        assert_eq!(
+            Err(PostError::MissingDate { index: 1 }),
+            Post::replay("ana", &events).map(|post| post.status())
+        );
+    }
+
+    #[test]
+    fn admins_can_force_publish() {
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        post.force_publish(&dani()).unwrap();
+        assert_eq!("Hello", post.content());
+
+        let tuesday = Utc.with_ymd_and_hms(2024, 5, 7, 9, 0, 0).unwrap();
+        let mut post = reviewed("Hello");
+        post.schedule(&ana(), tuesday).unwrap();
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        post.force_publish(&dani()).unwrap();
+        assert_eq!(Status::Published, post.status());
+
+        assert_eq!(
+            invalid(Action::ForcePublish, Status::Published),
+            post.force_publish(&dani())
+        );
+    }
+
+    #[test]
+    fn permissions_are_checked_first() {
+        let mut post = Post::with_author("ana");
+
+        assert_eq!(
+            denied(PermissionError::NotTheAuthor {
+                action: Action::RequestReview
+            }),
+            post.request_review(&bea())
+        );
+        post.request_review(&ana()).unwrap();
+
+        assert_eq!(
+            denied(PermissionError::RoleNotAllowed {
+                action: Action::Approve,
+                role: Role::Author
+            }),
+            post.approve(&User::author("eva"))
+        );
+        assert_eq!(
+            denied(PermissionError::RoleNotAllowed {
+                action: Action::Approve,
+                role: Role::Admin
+            }),
+            post.approve(&dani())
+        );
+        assert_eq!(
+            denied(PermissionError::RoleNotAllowed {
+                action: Action::Reject,
+                role: Role::Author
+            }),
+            post.reject(&ana())
+        );
+        assert_eq!(
+            denied(PermissionError::RoleNotAllowed {
+                action: Action::ForcePublish,
+                role: Role::Reviewer
+            }),
+            post.force_publish(&bea())
+        );
+        // Aunque el post no se pueda archivar, el error es el del permiso
+        assert_eq!(
+            denied(PermissionError::RoleNotAllowed {
+                action: Action::Archive,
+                role: Role::Author
+            }),
+            post.archive(&ana())
+        );
+        assert_eq!(Status::PendingReview, post.status());
+        assert_eq!(1, post.history().len());
+    }
+
+    #[test]
+    fn reviewers_cant_approve_their_own_posts() {
+        let bea = bea();
+        let mut post = Post::with_author(bea.name());
+        post.request_review(&bea).unwrap();
+
+        assert_eq!(
+            Err(PostError::PermissionDenied(PermissionError::OwnPost {
+                action: Action::Approve
+            })),
+            post.approve(&bea)
+        );
+        // Pero un admin puede mandar a revisión el post de otro
+        let mut post = Post::with_author("ana");
+        post.request_review(&dani()).unwrap();
+        post.approve(&bea).unwrap();
+    }
+
+    #[test]
+    fn every_edit_is_a_revision() {
+        let mut post = Post::with_author("ana");
+        post.add_text(&ana(), "Hello\n").unwrap();
+        post.add_text(&ana(), "world\n").unwrap();
+
+        let texts: Vec<&str> = post.revisions().iter().map(|r| r.content.as_str()).collect();
+        assert_eq!(vec!["", "Hello\n", "Hello\nworld\n"], texts);
+        assert_eq!("Hello\n", post.revision(2).unwrap().content);
+        assert_eq!(Err(PostError::UnknownRevision(0)), post.revision(0).map(|_| ()));
+        assert_eq!(Err(PostError::UnknownRevision(4)), post.diff(1, 4).map(|_| ()));
+
+        assert_eq!(
+            vec![
+                DiffLine::Same(String::from("Hello")),
+                DiffLine::Added(String::from("world"))
+            ],
+            post.diff(2, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn only_the_author_or_an_admin_edits() {
+        let mut post = Post::with_author("ana");
+        post.add_text(&ana(), "Hello\n").unwrap();
+
+        for user in [bea(), User::author("eva")] {
+            assert_eq!(
+                denied(PermissionError::NotTheAuthor {
+                    action: Action::AddText
+                }),
+                post.add_text(&user, "spam")
+            );
+            assert_eq!(
+                denied(PermissionError::NotTheAuthor {
+                    action: Action::Revert
+                }),
+                post.revert(&user, 1)
+            );
+        }
+        assert_eq!(2, post.revisions().len());
+
+        post.add_text(&dani(), "world\n").unwrap();
+        post.revert(&dani(), 2).unwrap();
+        assert_eq!("Hello\n", post.draft());
+    }
+
+    #[test]
+    fn only_drafts_can_be_reverted() {
+        let mut post = reviewed("Hello");
+        assert_eq!(invalid(Action::Revert, Status::PendingReview), post.revert(&ana(), 1));
+
+        post.reject(&bea()).unwrap();
+        post.revert(&ana(), 1).unwrap();
+        assert_eq!("", post.draft());
+        assert_eq!(3, post.revisions().len());
+        assert_eq!(Err(PostError::UnknownRevision(9)), post.revert(&ana(), 9));
+    }
+
+    #[test]
+    fn approved_content_is_frozen() {
+        let tuesday = Utc.with_ymd_and_hms(2024, 5, 7, 9, 0, 0).unwrap();
+        let mut post = reviewed("Hello");
+        post.approve(&bea()).unwrap();
+        assert_eq!(None, post.approved_revision());
+        post.approve(&carla()).unwrap();
+        assert_eq!(Some(2), post.approved_revision());
+
+        post.archive(&dani()).unwrap();
+        assert_eq!(Some(2), post.approved_revision());
+
+        // Al rechazar un post programado, el texto vuelve a poder cambiar
+        let mut post = reviewed("Hello");
+        post.schedule(&ana(), tuesday).unwrap();
+        post.approve(&bea()).unwrap();
+        post.approve(&carla()).unwrap();
+        assert_eq!(Some(2), post.approved_revision());
+        post.reject(&bea()).unwrap();
+        assert_eq!(None, post.approved_revision());
+
+        post.add_text(&ana(), ", world").unwrap();
+        post.request_review(&ana()).unwrap();
+        post.force_publish(&dani()).unwrap();
+        assert_eq!(Some(3), post.approved_revision());
+        assert_eq!("Hello, world", post.content());
+    }
+
+    #[test]
+    fn error_messages() {
+        let error = PostError::InvalidTransition {
+            action: Action::AddText,
+            from: Status::PendingReview,
+        };
+        assert_eq!(
+            "can't add text to a post that is pending review",
+            error.to_string()
+        );
+
+        let error = PostError::from(PermissionError::RoleNotAllowed {
+            action: Action::ForcePublish,
+            role: Role::Reviewer,
+        });
+        assert_eq!(
+            "permission denied: reviewers can't force-publish posts",
+            error.to_string()
+        );
+        assert_eq!(
+            "nobody can approve their own post",
+            PermissionError::OwnPost {
+                action: Action::Approve
+            }
+            .to_string()
+        );
+    }
+}
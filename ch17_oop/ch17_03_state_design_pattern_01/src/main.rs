@@ -3,17 +3,52 @@
 // https://doc.rust-lang.org/stable/book/ch17-03-oo-design-patterns.html
 //////////////////////////////
 
-use ch17_03_state_design_pattern_01::Post;
+use ch17_03_state_design_pattern_01::permissions::User;
+use ch17_03_state_design_pattern_01::{Event, Post, Status};
 
 fn main() {
-    let mut post = Post::new();
-    
-    post.add_text("I ate salad for lunch today");
+    let ana = User::author("ana");
+    let bea = User::reviewer("bea");
+    let carla = User::reviewer("carla");
+    let dani = User::admin("dani");
+
+    let mut post = Post::with_author(ana.name());
+
+    // Cada transición queda registrada: quién, cuándo y de qué estado a cuál
+    post.add_listener(|event: &Event| {
+        println!(
+            "{} {}: {} -> {}",
+            event.at, event.actor, event.from, event.to
+        )
+    });
+
+    post.add_text(&ana, "I ate salad for lunch today").unwrap();
     assert_eq!("", post.content());
-    
-    post.request_review();
+
+    post.request_review(&ana).unwrap();
     assert_eq!("", post.content());
-    
-    post.approve();
+
+    // Un revisor no está de acuerdo: vuelve a borrador
+    post.reject(&bea).unwrap();
+    assert_eq!(Status::Draft, post.status());
+    post.request_review(&ana).unwrap();
+
+    // Hacen falta dos aprobaciones para publicar, y solo los revisores aprueban
+    if let Err(e) = post.approve(&ana) {
+        println!("{}", e);
+    }
+    post.approve(&bea).unwrap();
+    assert_eq!("", post.content());
+
+    post.approve(&carla).unwrap();
     assert_eq!("I ate salad for lunch today", post.content());
+
+    // Las transiciones que no son válidas devuelven un error
+    if let Err(e) = post.add_text(&ana, "!") {
+        println!("{}", e);
+    }
+
+    // Solo un admin puede archivarlo
+    post.archive(&dani).unwrap();
+    assert_eq!("", post.content());
 }
@@ -0,0 +1,134 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/* Cada revisión guarda el texto completo del post en ese momento, no solo lo
+ * que cambió: así ver una revisión antigua o volver a ella es inmediato, y
+ * las diferencias se calculan solo cuando se piden.
+ *
+ * Las diferencias son por líneas y salen de la subsecuencia común más larga
+ * (LCS): las líneas que están en las dos versiones, en el mismo orden, se
+ * quedan igual, y el resto se han quitado o añadido.
+ */
+
+/// The text of a post after one of its edits. Revision 1 is the empty post,
+/// when it was created.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub number: usize,
+    pub content: String,
+    pub at: DateTime<Utc>,
+}
+
+/// A line in the difference between two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+impl fmt::Display for DiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffLine::Same(line) => write!(f, "  {}", line),
+            DiffLine::Added(line) => write!(f, "+ {}", line),
+            DiffLine::Removed(line) => write!(f, "- {}", line),
+        }
+    }
+}
+
+/// Compares two texts line by line. When a line changes, the old one comes
+/// first, as removed, and then the new one, as added.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_01::revisions::{diff, DiffLine};
+///
+/// let changes = diff("salad\nsoup", "salad\nsteak");
+/// assert_eq!(
+///     vec![
+///         DiffLine::Same(String::from("salad")),
+///         DiffLine::Removed(String::from("soup")),
+///         DiffLine::Added(String::from("steak")),
+///     ],
+///     changes
+/// );
+/// ```
+pub fn diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j]: longitud de la LCS de old[i..] y new[j..]
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(String::from(old[i])));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(DiffLine::Removed(String::from(old[i])));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(String::from(new[j])));
+            j += 1;
+        }
+    }
+    lines.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(String::from(*line))),
+    );
+    lines.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(String::from(*line))),
+    );
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(lines: &[DiffLine]) -> Vec<String> {
+        lines.iter().map(DiffLine::to_string).collect()
+    }
+
+    #[test]
+    fn identical_texts() {
+        assert_eq!(vec!["  a", "  b"], render(&diff("a\nb", "a\nb")));
+        assert!(diff("", "").is_empty());
+    }
+
+    #[test]
+    fn additions_and_removals() {
+        assert_eq!(vec!["+ a", "+ b"], render(&diff("", "a\nb")));
+        assert_eq!(vec!["- a", "- b"], render(&diff("a\nb", "")));
+        assert_eq!(
+            vec!["  a", "- b", "  c", "+ d"],
+            render(&diff("a\nb\nc", "a\nc\nd"))
+        );
+    }
+
+    #[test]
+    fn keeps_the_longest_common_part() {
+        assert_eq!(
+            vec!["- x", "  a", "  b", "- y", "+ z", "  c"],
+            render(&diff("x\na\nb\ny\nc", "a\nb\nz\nc"))
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::{Post, Status};
+
+/* El sitio se genera primero en memoria, como una lista de páginas con su
+ * ruta y su contenido, y después se escribe en disco. Así se puede comprobar
+ * lo generado sin tocar el sistema de archivos.
+ *
+ * Los posts no tienen título: se usa el primer encabezado del texto, o su
+ * primera línea. Del título sale el slug, que es el nombre de la página del
+ * post; si dos posts dan el mismo, el más reciente lleva un número detrás.
+ *
+ * Los enlaces entre páginas son relativos, para que el sitio funcione esté
+ * donde esté. El feed necesita direcciones completas, y ahí se usa base_url.
+ */
+
+/// A file of the generated site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// Relative to the root of the site, like `posts/hello.html`.
+    pub path: String,
+    pub contents: String,
+}
+
+/// Generates a static HTML site from the published posts: an index, a page
+/// per post and an Atom feed.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_01::permissions::User;
+/// use ch17_03_state_design_pattern_01::site::Site;
+/// use ch17_03_state_design_pattern_01::Post;
+///
+/// let ana = User::author("ana");
+/// let mut post = Post::with_author(ana.name());
+/// post.add_text(&ana, "# Hello, world\n\nMy *first* post.").unwrap();
+/// post.request_review(&ana).unwrap();
+/// post.force_publish(&User::admin("dani")).unwrap();
+///
+/// let site = Site::new("Ana's blog", "https://blog.example.com");
+/// let pages = site.render(&[post]);
+///
+/// let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+/// assert_eq!(vec!["index.html", "posts/hello-world.html", "feed.xml"], paths);
+/// assert!(pages[1].contents.contains("<p>My <em>first</em> post.</p>"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Site {
+    title: String,
+    base_url: String,
+}
+
+struct Entry<'a> {
+    slug: String,
+    title: String,
+    post: &'a Post,
+    published_at: DateTime<Utc>,
+}
+
+impl Site {
+    /// `base_url` is where the site will be served from, and is only used in
+    /// the feed.
+    pub fn new(title: &str, base_url: &str) -> Site {
+        Site {
+            title: String::from(title),
+            base_url: String::from(base_url.trim_end_matches('/')),
+        }
+    }
+
+    /// Generates every page of the site. Posts that aren't published are
+    /// left out.
+    pub fn render(&self, posts: &[Post]) -> Vec<Page> {
+        let entries = entries(posts);
+
+        let mut pages = vec![Page {
+            path: String::from("index.html"),
+            contents: self.index(&entries),
+        }];
+        for entry in &entries {
+            pages.push(Page {
+                path: format!("posts/{}.html", entry.slug),
+                contents: self.post_page(entry),
+            });
+        }
+        pages.push(Page {
+            path: String::from("feed.xml"),
+            contents: self.feed(&entries),
+        });
+        pages
+    }
+
+    /// Generates the site and writes it into `dir`, replacing the files that
+    /// were already there. Pages left in `posts/` by an earlier export, of
+    /// posts that were archived or retitled since, are removed.
+    pub fn write<P: AsRef<Path>>(&self, posts: &[Post], dir: P) -> io::Result<()> {
+        let dir = dir.as_ref();
+        let pages = self.render(posts);
+
+        fs::create_dir_all(dir.join("posts"))?;
+        for page in &pages {
+            fs::write(dir.join(&page.path), &page.contents)?;
+        }
+
+        let written: HashSet<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+        for entry in fs::read_dir(dir.join("posts"))? {
+            let entry = entry?;
+            let path = format!("posts/{}", entry.file_name().to_string_lossy());
+            if entry.file_type()?.is_file() && !written.contains(path.as_str()) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn index(&self, entries: &[Entry]) -> String {
+        let mut body = format!("<h1>{}</h1>\n", escape(&self.title));
+        if entries.is_empty() {
+            body.push_str("<p>Nothing published yet.</p>\n");
+        } else {
+            body.push_str("<ul class=\"posts\">\n");
+            for entry in entries {
+                body.push_str(&format!(
+                    "<li><a href=\"posts/{}.html\">{}</a> {}</li>\n",
+                    entry.slug,
+                    escape(&entry.title),
+                    byline(entry)
+                ));
+            }
+            body.push_str("</ul>\n");
+        }
+        self.layout(&self.title, "", &body)
+    }
+
+    fn post_page(&self, entry: &Entry) -> String {
+        let body = format!(
+            "<article>\n<p class=\"byline\">{}</p>\n{}</article>\n",
+            byline(entry),
+            markdown(entry.post.content())
+        );
+        let title = format!("{} - {}", entry.title, self.title);
+        self.layout(&title, "../", &body)
+    }
+
+    fn layout(&self, title: &str, root: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<link rel=\"alternate\" type=\"application/atom+xml\" title=\"{site}\" href=\"{root}feed.xml\">
+</head>
+<body>
+<header><a href=\"{root}index.html\">{site}</a></header>
+<main>
+{body}</main>
+</body>
+</html>
+",
+            title = escape(title),
+            site = escape(&self.title),
+            root = root,
+            body = body
+        )
+    }
+
+    fn feed(&self, entries: &[Entry]) -> String {
+        // Un feed vacío también necesita una fecha de actualización
+        let updated = entries
+            .iter()
+            .map(|entry| entry.published_at)
+            .max()
+            .unwrap_or(DateTime::UNIX_EPOCH);
+
+        let mut feed = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\">
+  <title>{title}</title>
+  <link href=\"{base}/\"/>
+  <link rel=\"self\" href=\"{base}/feed.xml\"/>
+  <id>{base}/</id>
+  <updated>{updated}</updated>
+",
+            title = escape(&self.title),
+            base = self.base_url,
+            updated = timestamp(updated)
+        );
+        for entry in entries {
+            let url = format!("{}/posts/{}.html", self.base_url, entry.slug);
+            feed.push_str(&format!(
+                "  <entry>
+    <title>{title}</title>
+    <link href=\"{url}\"/>
+    <id>{url}</id>
+    <updated>{updated}</updated>
+    <author><name>{author}</name></author>
+    <content type=\"html\">{content}</content>
+  </entry>
+",
+                title = escape(&entry.title),
+                url = url,
+                updated = timestamp(entry.published_at),
+                author = escape(entry.post.author()),
+                content = escape(&markdown(entry.post.content()))
+            ));
+        }
+        feed.push_str("</feed>\n");
+        feed
+    }
+}
+
+/// Renders Markdown as HTML.
+pub fn markdown(text: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new_ext(text, options));
+    output
+}
+
+// Los posts publicados, del más reciente al más antiguo
+fn entries(posts: &[Post]) -> Vec<Entry<'_>> {
+    let mut published: Vec<(&Post, DateTime<Utc>)> = posts
+        .iter()
+        .filter(|post| post.status() == Status::Published)
+        .map(|post| (post, published_at(post)))
+        .collect();
+    // Los slugs se reparten del más antiguo al más reciente, para que no
+    // cambien al publicar posts nuevos
+    published.sort_by_key(|&(_, at)| at);
+
+    let mut used = HashSet::new();
+    let mut entries: Vec<Entry> = published
+        .into_iter()
+        .map(|(post, published_at)| {
+            let title = title(post.content());
+            let base = slug(&title);
+            let mut slug = base.clone();
+            let mut n = 2;
+            while !used.insert(slug.clone()) {
+                slug = format!("{}-{}", base, n);
+                n += 1;
+            }
+            Entry {
+                slug,
+                title,
+                post,
+                published_at,
+            }
+        })
+        .collect();
+
+    entries.reverse();
+    entries
+}
+
+fn published_at(post: &Post) -> DateTime<Utc> {
+    post.history()
+        .iter()
+        .rev()
+        .find(|event| event.to == Status::Published)
+        .map_or(post.updated_at(), |event| event.at)
+}
+
+fn title(content: &str) -> String {
+    let heading = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("# "));
+    let first_line = content.lines().map(str::trim).find(|line| !line.is_empty());
+
+    match heading.or(first_line) {
+        Some(title) => String::from(title.trim_start_matches('#').trim()),
+        None => String::from("Untitled"),
+    }
+}
+
+fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        String::from("post")
+    } else {
+        String::from(slug)
+    }
+}
+
+fn byline(entry: &Entry) -> String {
+    format!(
+        "<time datetime=\"{}\">{}</time> by {}",
+        timestamp(entry.published_at),
+        entry.published_at.format("%Y-%m-%d"),
+        escape(entry.post.author())
+    )
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_come_from_the_first_heading() {
+        assert_eq!("Hello", title("Intro\n\n# Hello\n\n## Later"));
+        assert_eq!("Just text", title("\n  Just text\nmore"));
+        assert_eq!("Second level", title("## Second level\ntext"));
+        assert_eq!("Untitled", title(""));
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!("hello-world", slug("Hello, World!"));
+        assert_eq!("rust-2024-is-here", slug("  Rust 2024 -- is here  "));
+        assert_eq!("café-con-leche", slug("Café con leche"));
+        assert_eq!("post", slug("¿?"));
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;",
+            escape("<a href=\"x\">Tom & Jerry's</a>")
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::revisions::Revision;
+use crate::{
+    Action, Archived, Draft, Event, PendingReview, Post, Published, Scheduled, State, Status,
+    REQUIRED_APPROVALS,
+};
+
+/* El estado es un Box<dyn State> y no se puede serializar directamente, así
+ * que se guarda un registro plano con el nombre del estado y sus datos
+ * (aprobaciones, fecha de publicación) y al cargarlo se vuelve a construir.
+ * Los nombres se guardan como texto para poder dar un error propio cuando
+ * el estado no existe.
+ */
+
+/// A post as it's stored on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostRecord {
+    pub author: String,
+    pub content: String,
+    pub state: String,
+    #[serde(default)]
+    pub approvals: u32,
+    /// Who gave the approvals.
+    pub approvers: Vec<String>,
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub history: Vec<EventRecord>,
+    pub revisions: Vec<Revision>,
+    #[serde(default)]
+    pub approved_revision: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub action: String,
+    pub actor: String,
+    pub from: String,
+    pub to: String,
+    pub at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl EventRecord {
+    pub fn from_event(event: &Event) -> EventRecord {
+        EventRecord {
+            action: String::from(action_name(event.action)),
+            actor: event.actor.clone(),
+            from: String::from(status_name(event.from)),
+            to: String::from(status_name(event.to)),
+            at: event.at,
+            publish_at: event.publish_at,
+        }
+    }
+
+    pub fn to_event(&self) -> Result<Event, StorageError> {
+        Ok(Event {
+            action: parse_action(&self.action)?,
+            actor: self.actor.clone(),
+            from: parse_status(&self.from)?,
+            to: parse_status(&self.to)?,
+            at: self.at,
+            publish_at: self.publish_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// The file couldn't be read or written.
+    Io(String),
+    /// The file isn't valid JSON or doesn't describe posts.
+    Parse(String),
+    UnknownState(String),
+    UnknownAction(String),
+    /// The record has data that doesn't make sense for its state.
+    Inconsistent(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "couldn't access the store: {}", e),
+            StorageError::Parse(e) => write!(f, "invalid store: {}", e),
+            StorageError::UnknownState(state) => write!(f, "unknown post state '{}'", state),
+            StorageError::UnknownAction(action) => write!(f, "unknown action '{}'", action),
+            StorageError::Inconsistent(e) => write!(f, "inconsistent post: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> StorageError {
+        StorageError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> StorageError {
+        StorageError::Parse(e.to_string())
+    }
+}
+
+impl Post {
+    pub fn to_record(&self) -> PostRecord {
+        PostRecord {
+            author: self.author.clone(),
+            content: String::from(self.draft()),
+            state: String::from(status_name(self.status())),
+            approvals: self.approvals(),
+            approvers: self.approvers().to_vec(),
+            publish_at: self.publish_at(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            history: self.history.iter().map(EventRecord::from_event).collect(),
+            revisions: self.revisions.clone(),
+            approved_revision: self.approved_revision,
+        }
+    }
+
+    pub fn from_record(record: PostRecord) -> Result<Post, StorageError> {
+        let status = parse_status(&record.state)?;
+        let history = record
+            .history
+            .iter()
+            .map(EventRecord::to_event)
+            .collect::<Result<Vec<Event>, StorageError>>()?;
+
+        // Solo los posts en revisión guardan quién los aprobó
+        let approvers = match status {
+            Status::PendingReview => approvers_for(&record)?,
+            _ => Vec::new(),
+        };
+        let state = state_for(status, approvers, record.publish_at)?;
+
+        let revisions = revisions_for(&record)?;
+        let approved_revision = match (status, record.approved_revision) {
+            (Status::Draft | Status::PendingReview, None) => None,
+            (Status::Draft | Status::PendingReview, Some(_)) => {
+                return Err(StorageError::Inconsistent(String::from(
+                    "only scheduled or published posts have an approved revision",
+                )))
+            }
+            (_, Some(number)) if number == 0 || number > revisions.len() => {
+                return Err(StorageError::Inconsistent(format!(
+                    "there's no revision {}",
+                    number
+                )))
+            }
+            (_, Some(number)) => Some(number),
+            (_, None) => {
+                return Err(StorageError::Inconsistent(String::from(
+                    "scheduled and published posts need an approved revision",
+                )))
+            }
+        };
+
+        Ok(Post {
+            state: Some(state),
+            revisions,
+            approved_revision,
+            author: record.author,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            history,
+            listeners: Vec::new(),
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_record()).expect("records are always valid JSON")
+    }
+
+    pub fn from_json(json: &str) -> Result<Post, StorageError> {
+        Post::from_record(serde_json::from_str(json)?)
+    }
+}
+
+/// Keeps posts in a JSON file.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_01::permissions::User;
+/// use ch17_03_state_design_pattern_01::storage::JsonStore;
+/// use ch17_03_state_design_pattern_01::Post;
+///
+/// let path = std::env::temp_dir().join("blog_store_doc_example.json");
+/// let store = JsonStore::new(&path);
+///
+/// let ferris = User::author("ferris");
+/// let mut post = Post::with_author(ferris.name());
+/// post.add_text(&ferris, "Hello").unwrap();
+/// post.request_review(&ferris).unwrap();
+/// store.save(&[post]).unwrap();
+///
+/// let posts = store.load().unwrap();
+/// assert_eq!("ferris", posts[0].author());
+/// assert_eq!(1, posts[0].history().len());
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> JsonStore {
+        JsonStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Replaces the posts in the file. It's written to a temporary file
+    /// first, so a failure never leaves the store half written.
+    pub fn save(&self, posts: &[Post]) -> Result<(), StorageError> {
+        let records: Vec<PostRecord> = posts.iter().map(Post::to_record).collect();
+        let json = serde_json::to_string_pretty(&records)?;
+
+        let temporary = self.path.with_extension("json.tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    /// Loads every post. A store that was never saved is empty.
+    pub fn load(&self) -> Result<Vec<Post>, StorageError> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let records: Vec<PostRecord> = serde_json::from_str(&json)?;
+        records.into_iter().map(Post::from_record).collect()
+    }
+}
+
+fn revisions_for(record: &PostRecord) -> Result<Vec<Revision>, StorageError> {
+    if record.revisions.is_empty() {
+        return Err(StorageError::Inconsistent(String::from(
+            "a post has at least one revision",
+        )));
+    }
+
+    if let Some((index, _)) = record
+        .revisions
+        .iter()
+        .enumerate()
+        .find(|(index, revision)| revision.number != index + 1)
+    {
+        return Err(StorageError::Inconsistent(format!(
+            "revision {} is out of order",
+            index + 1
+        )));
+    }
+    if record.revisions.last().map(|revision| &revision.content) != Some(&record.content) {
+        return Err(StorageError::Inconsistent(String::from(
+            "the content isn't the one of the latest revision",
+        )));
+    }
+    Ok(record.revisions.clone())
+}
+
+// Cada aprobación tiene que ser de un revisor distinto
+fn approvers_for(record: &PostRecord) -> Result<Vec<String>, StorageError> {
+    let approvers = record.approvers.clone();
+
+    if approvers.len() != record.approvals as usize {
+        return Err(StorageError::Inconsistent(format!(
+            "{} approvals, but {} approvers",
+            record.approvals,
+            approvers.len()
+        )));
+    }
+    for (i, name) in approvers.iter().enumerate() {
+        if approvers[..i].contains(name) {
+            return Err(StorageError::Inconsistent(format!(
+                "{} approved the post twice",
+                name
+            )));
+        }
+    }
+    Ok(approvers)
+}
+
+fn state_for(
+    status: Status,
+    approvers: Vec<String>,
+    publish_at: Option<DateTime<Utc>>,
+) -> Result<Box<dyn State>, StorageError> {
+    match status {
+        Status::PendingReview if approvers.len() as u32 >= REQUIRED_APPROVALS => {
+            Err(StorageError::Inconsistent(format!(
+                "a post in review can't have {} approvals",
+                approvers.len()
+            )))
+        }
+        Status::PendingReview => Ok(Box::new(PendingReview {
+            approvers,
+            publish_at,
+        })),
+        Status::Scheduled => match publish_at {
+            Some(publish_at) => Ok(Box::new(Scheduled { publish_at })),
+            None => Err(StorageError::Inconsistent(String::from(
+                "a scheduled post needs a publication date",
+            ))),
+        },
+        Status::Draft => Ok(Box::new(Draft {})),
+        Status::Published => Ok(Box::new(Published {})),
+        Status::Archived => Ok(Box::new(Archived {})),
+    }
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Draft => "draft",
+        Status::PendingReview => "pending_review",
+        Status::Scheduled => "scheduled",
+        Status::Published => "published",
+        Status::Archived => "archived",
+    }
+}
+
+fn parse_status(name: &str) -> Result<Status, StorageError> {
+    [
+        Status::Draft,
+        Status::PendingReview,
+        Status::Scheduled,
+        Status::Published,
+        Status::Archived,
+    ]
+    .into_iter()
+    .find(|&status| status_name(status) == name)
+    .ok_or_else(|| StorageError::UnknownState(String::from(name)))
+}
+
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::AddText => "add_text",
+        Action::RequestReview => "request_review",
+        Action::Approve => "approve",
+        Action::Reject => "reject",
+        Action::Schedule => "schedule",
+        Action::Publish => "publish",
+        Action::ForcePublish => "force_publish",
+        Action::Archive => "archive",
+        Action::Revert => "revert",
+    }
+}
+
+fn parse_action(name: &str) -> Result<Action, StorageError> {
+    [
+        Action::AddText,
+        Action::RequestReview,
+        Action::Approve,
+        Action::Reject,
+        Action::Schedule,
+        Action::Publish,
+        Action::ForcePublish,
+        Action::Archive,
+        Action::Revert,
+    ]
+    .into_iter()
+    .find(|&action| action_name(action) == name)
+    .ok_or_else(|| StorageError::UnknownAction(String::from(name)))
+}
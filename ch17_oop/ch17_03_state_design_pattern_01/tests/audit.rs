@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ch17_03_state_design_pattern_01::audit::AuditLog;
+use ch17_03_state_design_pattern_01::permissions::User;
+use ch17_03_state_design_pattern_01::storage::StorageError;
+use ch17_03_state_design_pattern_01::{Action, Post, Status};
+use chrono::{TimeZone, Utc};
+
+// Cada test usa su propio archivo para poder ejecutarse en paralelo
+fn log_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("blog_audit_{}_{}.log", name, std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn every_transition_is_appended() {
+    let path = log_path("appended");
+    let publish_at = Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap();
+
+    let (ana, bea, carla) = (
+        User::author("ana"),
+        User::reviewer("bea"),
+        User::reviewer("carla"),
+    );
+    let mut post = Post::with_author("ana");
+    post.add_listener(AuditLog::open(&path, "hello").unwrap());
+    post.add_text(&ana, "Hello").unwrap();
+    post.request_review(&ana).unwrap();
+    post.schedule(&ana, publish_at).unwrap();
+    post.approve(&bea).unwrap();
+
+    let events = AuditLog::read_post(&path, "hello").unwrap();
+    assert_eq!(post.history(), &events[..]);
+    assert_eq!(Some(publish_at), events[1].publish_at);
+    // Las líneas añadidas no cambian, solo se agregan más
+    let before = fs::read_to_string(&path).unwrap();
+    post.approve(&carla).unwrap();
+    let after = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(after.starts_with(&before));
+    assert!(before.lines().all(|line| line.contains("\"post\":\"hello\"")));
+    assert_eq!(3, before.lines().count());
+    assert_eq!(4, after.lines().count());
+}
+
+#[test]
+fn reopening_keeps_earlier_events() {
+    let path = log_path("reopen");
+
+    let (ana, bea, carla) = (
+        User::author("ana"),
+        User::reviewer("bea"),
+        User::reviewer("carla"),
+    );
+    let mut post = Post::with_author("ana");
+    post.add_listener(AuditLog::open(&path, "hello").unwrap());
+    post.request_review(&ana).unwrap();
+    post.approve(&bea).unwrap();
+
+    // Por ejemplo, después de cargar el post desde el almacén
+    let mut post = Post::from_json(&post.to_json()).unwrap();
+    post.add_listener(AuditLog::open(&path, "hello").unwrap());
+    post.approve(&carla).unwrap();
+    post.archive(&User::admin("dani")).unwrap();
+
+    let events = AuditLog::read_post(&path, "hello").unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let actions: Vec<Action> = events.iter().map(|e| e.action).collect();
+    assert_eq!(
+        vec![
+            Action::RequestReview,
+            Action::Approve,
+            Action::Approve,
+            Action::Archive
+        ],
+        actions
+    );
+    assert_eq!(
+        Status::Archived,
+        Post::replay("ana", &events).unwrap().status()
+    );
+}
+
+#[test]
+fn posts_share_a_log() {
+    let path = log_path("shared");
+    let (ana, bea) = (User::author("ana"), User::reviewer("bea"));
+
+    let mut first = Post::with_author("ana");
+    first.add_listener(AuditLog::open(&path, "first").unwrap());
+    let mut second = Post::with_author("ana");
+    second.add_listener(AuditLog::open(&path, "second").unwrap());
+
+    first.request_review(&ana).unwrap();
+    second.request_review(&ana).unwrap();
+    second.reject(&bea).unwrap();
+    first.approve(&bea).unwrap();
+
+    let entries = AuditLog::read(&path).unwrap();
+    let first_events = AuditLog::read_post(&path, "first").unwrap();
+    let second_events = AuditLog::read_post(&path, "second").unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let posts: Vec<&str> = entries.iter().map(|e| e.post.as_str()).collect();
+    assert_eq!(vec!["first", "second", "second", "first"], posts);
+    assert_eq!(first.history(), &first_events[..]);
+    assert_eq!(
+        Status::Draft,
+        Post::replay("ana", &second_events).unwrap().status()
+    );
+}
+
+// /dev/full acepta abrirse, pero todas las escrituras fallan
+#[cfg(target_os = "linux")]
+#[test]
+fn failed_writes_can_be_checked() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let log = Rc::new(RefCell::new(AuditLog::open("/dev/full", "hello").unwrap()));
+    let mut post = Post::with_author("ana");
+    post.add_listener(Rc::clone(&log));
+
+    post.request_review(&User::author("ana")).unwrap();
+    post.reject(&User::reviewer("bea")).unwrap();
+
+    let log = log.borrow();
+    assert_eq!(2, log.errors().len());
+    assert!(matches!(log.errors()[0], StorageError::Io(_)));
+    // La transición se hace igualmente
+    assert_eq!(2, post.history().len());
+}
+
+#[test]
+fn missing_log_is_empty() {
+    assert_eq!(Ok(Vec::new()), AuditLog::read(log_path("missing")));
+}
+
+#[test]
+fn corrupted_logs_are_refused() {
+    let path = log_path("corrupted");
+    fs::write(&path, "{ not json\n").unwrap();
+    let result = AuditLog::read(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(StorageError::Parse(_))));
+}
@@ -0,0 +1,125 @@
+use std::fs;
+
+use ch17_03_state_design_pattern_01::site::Site;
+use ch17_03_state_design_pattern_01::Post;
+
+/* Las páginas generadas se comparan con las de tests/snapshots. Si un cambio
+ * en el generador es a propósito, se actualizan con
+ * INSTA_UPDATE=always cargo test --test site
+ * (o con cargo insta review) y se revisa la diferencia.
+ */
+
+// Un post cargado del almacén, para que las fechas no cambien entre ejecuciones
+fn post(author: &str, state: &str, content: &str, published_at: &str) -> Post {
+    let record = serde_json::json!({
+        "author": author,
+        "content": content,
+        "state": state,
+        "approvers": [],
+        "created_at": "2024-05-01T09:00:00Z",
+        "updated_at": published_at,
+        "revisions": [
+            { "number": 1, "content": "", "at": "2024-05-01T09:00:00Z" },
+            { "number": 2, "content": content, "at": "2024-05-01T09:30:00Z" }
+        ],
+        "approved_revision": 2,
+        "history": [
+            { "action": "request_review", "actor": author, "from": "draft", "to": "pending_review", "at": "2024-05-01T10:00:00Z" },
+            { "action": "force_publish", "actor": "dani", "from": "pending_review", "to": "published", "at": published_at }
+        ]
+    });
+    Post::from_json(&record.to_string()).unwrap()
+}
+
+fn posts() -> Vec<Post> {
+    vec![
+        post(
+            "ana",
+            "published",
+            "# Hello, world\n\nMy *first* post, with `code` and a [link](https://www.rust-lang.org).\n",
+            "2024-05-02T09:00:00Z",
+        ),
+        post(
+            "bea",
+            "published",
+            "# Tom & Jerry <3\n\n- one\n- two\n\n```rust\nfn main() {}\n```\n",
+            "2024-05-03T18:30:00Z",
+        ),
+        post("carla", "archived", "# Gone\n\nNot shown.", "2024-05-04T09:00:00Z"),
+        post(
+            "dani",
+            "published",
+            "# Hello, world\n\nSame title, newer post.",
+            "2024-05-05T12:00:00Z",
+        ),
+    ]
+}
+
+fn site() -> Site {
+    Site::new("The Blog", "https://blog.example.com/")
+}
+
+#[test]
+fn pages_of_the_site() {
+    let pages = site().render(&posts());
+
+    let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+    assert_eq!(
+        vec![
+            "index.html",
+            "posts/hello-world-2.html",
+            "posts/tom-jerry-3.html",
+            "posts/hello-world.html",
+            "feed.xml"
+        ],
+        paths
+    );
+
+    for page in &pages {
+        insta::assert_snapshot!(page.path.replace('/', "__"), page.contents);
+    }
+}
+
+#[test]
+fn empty_site() {
+    let pages = site().render(&[]);
+
+    assert_eq!(2, pages.len());
+    insta::assert_snapshot!("empty_index", pages[0].contents);
+    insta::assert_snapshot!("empty_feed", pages[1].contents);
+}
+
+#[test]
+fn writes_the_pages_to_disk() {
+    let dir = std::env::temp_dir().join(format!("blog_site_{}", std::process::id()));
+    site().write(&posts(), &dir).unwrap();
+
+    let index = fs::read_to_string(dir.join("index.html")).unwrap();
+    let post = fs::read_to_string(dir.join("posts/tom-jerry-3.html")).unwrap();
+    let feed_exists = dir.join("feed.xml").exists();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(index.contains("href=\"posts/tom-jerry-3.html\""));
+    assert!(post.contains("<h1>Tom &amp; Jerry &lt;3</h1>"));
+    assert!(feed_exists);
+}
+
+#[test]
+fn pages_of_unpublished_posts_are_removed() {
+    let dir = std::env::temp_dir().join(format!("blog_site_stale_{}", std::process::id()));
+    let mut posts = posts();
+    site().write(&posts, &dir).unwrap();
+    assert!(dir.join("posts/tom-jerry-3.html").exists());
+
+    // Bea archiva su post y se vuelve a exportar
+    posts[1] = post("bea", "archived", "# Tom & Jerry <3", "2024-05-03T18:30:00Z");
+    site().write(&posts, &dir).unwrap();
+    let mut files: Vec<String> = fs::read_dir(dir.join("posts"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    files.sort();
+    assert_eq!(vec!["hello-world-2.html", "hello-world.html"], files);
+}
@@ -0,0 +1,255 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ch17_03_state_design_pattern_01::permissions::User;
+use ch17_03_state_design_pattern_01::storage::{JsonStore, StorageError};
+use ch17_03_state_design_pattern_01::{Action, Post, Status};
+use chrono::{TimeZone, Utc};
+
+// Cada test usa su propio archivo para poder ejecutarse en paralelo
+fn store_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("blog_{}_{}.json", name, std::process::id()))
+}
+
+fn posts() -> Vec<Post> {
+    let ana = User::author("ana");
+    let bea = User::reviewer("bea");
+    let carla = User::reviewer("carla");
+    let dani = User::admin("dani");
+
+    let mut draft = Post::with_author("ana");
+    draft.add_text(&ana, "Work in progress").unwrap();
+
+    let mut reviewed = Post::with_author("ana");
+    reviewed.add_text(&ana, "Almost there").unwrap();
+    reviewed.request_review(&ana).unwrap();
+    reviewed.approve(&bea).unwrap();
+
+    let mut scheduled = Post::with_author("ana");
+    scheduled.add_text(&ana, "See you tomorrow").unwrap();
+    scheduled.request_review(&ana).unwrap();
+    scheduled
+        .schedule(&ana, Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap())
+        .unwrap();
+    scheduled.approve(&bea).unwrap();
+    scheduled.approve(&carla).unwrap();
+
+    let mut archived = Post::with_author("ana");
+    archived.add_text(&ana, "Old news").unwrap();
+    archived.request_review(&ana).unwrap();
+    archived.reject(&bea).unwrap();
+    archived.request_review(&ana).unwrap();
+    archived.approve(&bea).unwrap();
+    archived.approve(&carla).unwrap();
+    archived.archive(&dani).unwrap();
+
+    vec![draft, reviewed, scheduled, archived]
+}
+
+#[test]
+fn round_trip_through_a_file() {
+    let path = store_path("round_trip");
+    let store = JsonStore::new(&path);
+    let posts = posts();
+
+    store.save(&posts).unwrap();
+    let loaded = store.load().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(posts.len(), loaded.len());
+    for (post, loaded) in posts.iter().zip(&loaded) {
+        assert_eq!(post.to_record(), loaded.to_record());
+    }
+
+    let statuses: Vec<Status> = loaded.iter().map(Post::status).collect();
+    assert_eq!(
+        vec![
+            Status::Draft,
+            Status::PendingReview,
+            Status::Scheduled,
+            Status::Archived
+        ],
+        statuses
+    );
+    let actions: Vec<Action> = loaded[3].history().iter().map(|e| e.action).collect();
+    assert_eq!(
+        vec![
+            Action::RequestReview,
+            Action::Reject,
+            Action::RequestReview,
+            Action::Approve,
+            Action::Approve,
+            Action::Archive
+        ],
+        actions
+    );
+}
+
+#[test]
+fn loaded_posts_keep_working() {
+    let json = posts()[1].to_json();
+    let mut post = Post::from_json(&json).unwrap();
+
+    assert_eq!(1, post.approvals());
+    post.approve(&User::reviewer("carla")).unwrap();
+    assert_eq!("Almost there", post.content());
+    assert_eq!(3, post.history().len());
+}
+
+#[test]
+fn approvers_are_kept() {
+    let mut post = Post::from_json(&posts()[1].to_json()).unwrap();
+    assert_eq!(vec!["bea"], post.approvers());
+    assert!(post.approve(&User::reviewer("bea")).is_err());
+
+    let mut record = posts()[1].to_record();
+    record.approvers.clear();
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[1].to_record();
+    record.approvals = 2;
+    record.approvers.push(String::from("bea"));
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+}
+
+#[test]
+fn missing_store_is_empty() {
+    let store = JsonStore::new(store_path("missing"));
+    assert_eq!(0, store.load().unwrap().len());
+}
+
+#[test]
+fn unknown_states_are_refused() {
+    let mut record = posts()[0].to_record();
+    record.state = String::from("deleted");
+    assert_eq!(
+        Err(StorageError::UnknownState(String::from("deleted"))),
+        Post::from_record(record).map(|post| post.status())
+    );
+
+    let mut record = posts()[3].to_record();
+    record.history[0].to = String::from("limbo");
+    assert_eq!(
+        Err(StorageError::UnknownState(String::from("limbo"))),
+        Post::from_record(record).map(|post| post.status())
+    );
+
+    let mut record = posts()[3].to_record();
+    record.history[0].action = String::from("delete");
+    assert_eq!(
+        Err(StorageError::UnknownAction(String::from("delete"))),
+        Post::from_record(record).map(|post| post.status())
+    );
+}
+
+#[test]
+fn inconsistent_records_are_refused() {
+    let mut record = posts()[2].to_record();
+    record.publish_at = None;
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[1].to_record();
+    record.approvals = 2;
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+}
+
+#[test]
+fn invalid_files() {
+    let path = store_path("invalid");
+    fs::write(&path, "{ not json").unwrap();
+    let result = JsonStore::new(&path).load();
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(result, Err(StorageError::Parse(_))));
+}
+
+#[test]
+fn events_keep_their_actor() {
+    let record = posts()[3].to_record();
+    let actors: Vec<&str> = record.history.iter().map(|e| e.actor.as_str()).collect();
+    assert_eq!(vec!["ana", "bea", "ana", "bea", "carla", "dani"], actors);
+
+    // Todos los eventos guardan quién los hizo
+    let json = r#"{
+        "author": "ana",
+        "content": "Old",
+        "state": "pending_review",
+        "approvers": [],
+        "created_at": "2024-05-06T09:00:00Z",
+        "updated_at": "2024-05-06T09:00:00Z",
+        "revisions": [
+            { "number": 1, "content": "", "at": "2024-05-06T09:00:00Z" },
+            { "number": 2, "content": "Old", "at": "2024-05-06T09:00:00Z" }
+        ],
+        "history": [
+            { "action": "request_review", "from": "draft", "to": "pending_review", "at": "2024-05-06T09:00:00Z" }
+        ]
+    }"#;
+    assert!(matches!(Post::from_json(json), Err(StorageError::Parse(e)) if e.contains("actor")));
+}
+
+#[test]
+fn revisions_are_stored() {
+    let ana = User::author("ana");
+    let mut post = Post::with_author("ana");
+    post.add_text(&ana, "Hello\n").unwrap();
+    post.add_text(&ana, "world\n").unwrap();
+    post.revert(&ana, 2).unwrap();
+
+    let loaded = Post::from_json(&post.to_json()).unwrap();
+    assert_eq!(post.revisions(), loaded.revisions());
+    assert_eq!("Hello\n", loaded.draft());
+
+    let published = Post::from_json(&posts()[3].to_json()).unwrap();
+    assert_eq!(Some(2), published.approved_revision());
+}
+
+#[test]
+fn inconsistent_revisions_are_refused() {
+    let mut record = posts()[1].to_record();
+    record.revisions[1].content = String::from("Something else");
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[1].to_record();
+    record.revisions.remove(0);
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[3].to_record();
+    record.approved_revision = Some(7);
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[3].to_record();
+    record.approved_revision = None;
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[0].to_record();
+    record.revisions.clear();
+    assert!(matches!(
+        Post::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+}
@@ -1,43 +1,454 @@
+/* Cada estado del post es un tipo distinto, así que el compilador impide las
+ * transiciones que no son válidas: un DraftPost no tiene approve() y un
+ * PendingReviewPost no tiene content().
+ *
+ * Las aprobaciones se cuentan con un const generic: PendingReviewPost<0>
+ * se convierte en PendingReviewPost<1> al aprobarlo, y solo este último se
+ * publica con la segunda aprobación.
+ *
+ * Quién puede hacer cada transición se comprueba al ejecutar (ver
+ * permissions), así que las transiciones devuelven un Result. El error
+ * devuelve el post entero para no perderlo, aunque ocupe bastante.
+ */
+
+#![allow(clippy::result_large_err)]
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+pub mod permissions;
+pub mod storage;
+
+use permissions::{PermissionError, Refused, User};
+
+/// Approvals a post needs before it's published.
+pub const REQUIRED_APPROVALS: u32 = 2;
+
+/// A published post.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_02::permissions::User;
+/// use ch17_03_state_design_pattern_02::Post;
+///
+/// let ana = User::author("ana");
+/// let mut post = Post::with_author(ana.name());
+/// post.add_text(&ana, "I ate salad for lunch today").unwrap();
+///
+/// let post = post.request_review(&ana).unwrap();
+/// let post = post.approve(&User::reviewer("bea")).unwrap();
+/// assert_eq!(1, post.approvals());
+///
+/// let post = post.approve(&User::reviewer("carla")).unwrap();
+/// assert_eq!("I ate salad for lunch today", post.content());
+/// ```
+///
+/// Publishing with a single approval doesn't compile:
+/// ```compile_fail
+/// use ch17_03_state_design_pattern_02::permissions::User;
+/// use ch17_03_state_design_pattern_02::Post;
+///
+/// let ana = User::author("ana");
+/// let post = Post::with_author("ana").request_review(&ana).unwrap();
+/// let post = post.approve(&User::reviewer("bea")).unwrap();
+/// post.content();
+/// ```
+#[derive(Debug)]
 pub struct Post {
     content: String,
+    meta: Meta,
 }
 
+#[derive(Debug)]
 pub struct DraftPost {
     content: String,
+    meta: Meta,
+}
+
+/// A post waiting for review, with `APPROVALS` approvals so far.
+#[derive(Debug)]
+pub struct PendingReviewPost<const APPROVALS: u32> {
+    content: String,
+    meta: Meta,
 }
 
-pub struct PendingReviewPost {
+/// A post an admin took down. Its text is no longer shown.
+#[derive(Debug)]
+pub struct ArchivedPost {
     content: String,
+    meta: Meta,
+}
+
+/// The stages a post goes through, as recorded in its history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Draft,
+    PendingReview,
+    Published,
+    Archived,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    AddText,
+    RequestReview,
+    Approve,
+    Reject,
+    ForcePublish,
+    Archive,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Action::AddText => "add text to",
+            Action::RequestReview => "request review of",
+            Action::Approve => "approve",
+            Action::Reject => "reject",
+            Action::ForcePublish => "force-publish",
+            Action::Archive => "archive",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A transition in the history of a post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub action: Action,
+    /// Who did it.
+    pub actor: String,
+    pub from: Stage,
+    pub to: Stage,
+    pub at: DateTime<Utc>,
+}
+
+/// What every stage of a post knows besides its text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meta {
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    /// Last time the text or the stage changed.
+    pub updated_at: DateTime<Utc>,
+    /// Every transition, oldest first.
+    pub history: Vec<Event>,
+    /// Who approved the post since it was last sent to review.
+    pub approvers: Vec<String>,
+}
+
+impl Meta {
+    fn new(author: &str) -> Meta {
+        let now = Utc::now();
+        Meta {
+            author: String::from(author),
+            created_at: now,
+            updated_at: now,
+            history: Vec::new(),
+            approvers: Vec::new(),
+        }
+    }
+
+    fn check(&self, action: Action, user: &User) -> Result<(), PermissionError> {
+        permissions::check(action, user, &self.author)?;
+        // El tipo solo cuenta aprobaciones; quién las dio se comprueba aquí
+        if action == Action::Approve && self.approvers.iter().any(|name| name == user.name()) {
+            return Err(PermissionError::AlreadyApproved);
+        }
+        Ok(())
+    }
+
+    // Apunta la transición y devuelve los metadatos para el siguiente tipo
+    fn record(mut self, action: Action, user: &User, from: Stage, to: Stage) -> Meta {
+        match action {
+            Action::RequestReview | Action::Reject => self.approvers.clear(),
+            Action::Approve => self.approvers.push(String::from(user.name())),
+            Action::AddText | Action::ForcePublish | Action::Archive => {}
+        }
+
+        let at = Utc::now();
+        self.history.push(Event {
+            action,
+            actor: String::from(user.name()),
+            from,
+            to,
+            at,
+        });
+        self.updated_at = at;
+        self
+    }
 }
 
 impl Post {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> DraftPost {
+        Post::with_author("")
+    }
+
+    pub fn with_author(author: &str) -> DraftPost {
         DraftPost {
             content: String::new(),
+            meta: Meta::new(author),
         }
     }
 
     pub fn content(&self) -> &str {
         &self.content
     }
+
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    /// Takes the post down. Only admins can.
+    pub fn archive(self, user: &User) -> Result<ArchivedPost, Refused<Post>> {
+        if let Err(error) = self.meta.check(Action::Archive, user) {
+            return Err(Refused { post: self, error });
+        }
+        Ok(ArchivedPost {
+            content: self.content,
+            meta: self
+                .meta
+                .record(Action::Archive, user, Stage::Published, Stage::Archived),
+        })
+    }
 }
 
 impl DraftPost {
-    pub fn add_text(&mut self, text: &str) {
+    /// Adds text to the draft. Only its author or an admin can.
+    pub fn add_text(&mut self, user: &User, text: &str) -> Result<(), PermissionError> {
+        self.meta.check(Action::AddText, user)?;
         self.content.push_str(text);
+        self.meta.updated_at = Utc::now();
+        Ok(())
     }
 
-    pub fn request_review(self) -> PendingReviewPost {
-        PendingReviewPost {
-            content: self.content
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    pub fn request_review(self, user: &User) -> Result<PendingReviewPost<0>, Refused<DraftPost>> {
+        if let Err(error) = self.meta.check(Action::RequestReview, user) {
+            return Err(Refused { post: self, error });
         }
+        Ok(PendingReviewPost {
+            content: self.content,
+            meta: self.meta.record(
+                Action::RequestReview,
+                user,
+                Stage::Draft,
+                Stage::PendingReview,
+            ),
+        })
     }
 }
 
-impl PendingReviewPost {
-    pub fn approve(self) -> Post {
-        Post {
-            content: self.content
+impl<const APPROVALS: u32> PendingReviewPost<APPROVALS> {
+    pub fn approvals(&self) -> u32 {
+        APPROVALS
+    }
+
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+
+    /// Sends the post back to draft. Its approvals are lost.
+    pub fn reject(self, user: &User) -> Result<DraftPost, Refused<Self>> {
+        if let Err(error) = self.meta.check(Action::Reject, user) {
+            return Err(Refused { post: self, error });
         }
+        Ok(DraftPost {
+            content: self.content,
+            meta: self
+                .meta
+                .record(Action::Reject, user, Stage::PendingReview, Stage::Draft),
+        })
     }
-}
\ No newline at end of file
+
+    /// Publishes the post right away, whatever its approvals. Only admins can.
+    pub fn force_publish(self, user: &User) -> Result<Post, Refused<Self>> {
+        if let Err(error) = self.meta.check(Action::ForcePublish, user) {
+            return Err(Refused { post: self, error });
+        }
+        Ok(Post {
+            content: self.content,
+            meta: self.meta.record(
+                Action::ForcePublish,
+                user,
+                Stage::PendingReview,
+                Stage::Published,
+            ),
+        })
+    }
+}
+
+impl PendingReviewPost<0> {
+    pub fn approve(self, user: &User) -> Result<PendingReviewPost<1>, Refused<Self>> {
+        if let Err(error) = self.meta.check(Action::Approve, user) {
+            return Err(Refused { post: self, error });
+        }
+        Ok(PendingReviewPost {
+            content: self.content,
+            meta: self.meta.record(
+                Action::Approve,
+                user,
+                Stage::PendingReview,
+                Stage::PendingReview,
+            ),
+        })
+    }
+}
+
+impl PendingReviewPost<1> {
+    pub fn approve(self, user: &User) -> Result<Post, Refused<Self>> {
+        if let Err(error) = self.meta.check(Action::Approve, user) {
+            return Err(Refused { post: self, error });
+        }
+        Ok(Post {
+            content: self.content,
+            meta: self.meta.record(
+                Action::Approve,
+                user,
+                Stage::PendingReview,
+                Stage::Published,
+            ),
+        })
+    }
+}
+
+impl ArchivedPost {
+    pub fn meta(&self) -> &Meta {
+        &self.meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::permissions::Role;
+
+    fn ana() -> User {
+        User::author("ana")
+    }
+
+    fn bea() -> User {
+        User::reviewer("bea")
+    }
+
+    fn carla() -> User {
+        User::reviewer("carla")
+    }
+
+    fn dani() -> User {
+        User::admin("dani")
+    }
+
+    fn draft(text: &str) -> DraftPost {
+        let mut post = Post::with_author("ana");
+        post.add_text(&ana(), text).unwrap();
+        post
+    }
+
+    #[test]
+    fn two_approvals_publish() {
+        let post = draft("Hello").request_review(&ana()).unwrap();
+        assert_eq!(0, post.approvals());
+        let post = post.approve(&bea()).unwrap();
+        assert_eq!(1, post.approvals());
+        let post = post.approve(&carla()).unwrap();
+        assert_eq!("Hello", post.content());
+    }
+
+    #[test]
+    fn each_reviewer_approves_once() {
+        let post = draft("Hello").request_review(&ana()).unwrap();
+        let post = post.approve(&bea()).unwrap();
+
+        let refused = post.approve(&bea()).unwrap_err();
+        assert_eq!(PermissionError::AlreadyApproved, refused.error);
+        assert_eq!(vec!["bea"], refused.post.meta().approvers);
+        assert_eq!(2, refused.post.meta().history.len());
+
+        let post = refused.post.approve(&carla()).unwrap();
+        assert_eq!(vec!["bea", "carla"], post.meta().approvers);
+    }
+
+    #[test]
+    fn only_the_author_or_an_admin_edits() {
+        let mut post = draft("Hello");
+        for user in [bea(), User::author("eva")] {
+            assert_eq!(
+                Err(PermissionError::NotTheAuthor {
+                    action: Action::AddText
+                }),
+                post.add_text(&user, " spam")
+            );
+        }
+
+        post.add_text(&dani(), ", world").unwrap();
+        let post = post.request_review(&ana()).unwrap().force_publish(&dani());
+        assert_eq!("Hello, world", post.unwrap().content());
+    }
+
+    #[test]
+    fn rejected_posts_start_over() {
+        let post = draft("Hello").request_review(&ana()).unwrap();
+        let mut post = post.approve(&bea()).unwrap().reject(&carla()).unwrap();
+        post.add_text(&ana(), ", world").unwrap();
+
+        // Vuelve a necesitar las dos aprobaciones
+        let post = post.request_review(&ana()).unwrap();
+        assert_eq!(0, post.approvals());
+        let post = post.approve(&bea()).unwrap().approve(&carla()).unwrap();
+        assert_eq!("Hello, world", post.content());
+    }
+
+    #[test]
+    fn admins_force_publish_and_archive() {
+        let post = draft("Hello").request_review(&ana()).unwrap();
+        let post = post.force_publish(&dani()).unwrap();
+        assert_eq!("Hello", post.content());
+
+        let post = post.archive(&dani()).unwrap();
+        let actions: Vec<Action> = post.meta().history.iter().map(|e| e.action).collect();
+        assert_eq!(
+            vec![Action::RequestReview, Action::ForcePublish, Action::Archive],
+            actions
+        );
+        assert_eq!("dani", post.meta().history[2].actor);
+    }
+
+    #[test]
+    fn refused_transitions_give_the_post_back() {
+        let refused = draft("Hello").request_review(&bea()).unwrap_err();
+        assert_eq!(
+            PermissionError::NotTheAuthor {
+                action: Action::RequestReview
+            },
+            refused.error
+        );
+
+        let post = refused.post.request_review(&ana()).unwrap();
+        let refused = post.approve(&dani()).unwrap_err();
+        assert_eq!(
+            PermissionError::RoleNotAllowed {
+                action: Action::Approve,
+                role: Role::Admin
+            },
+            refused.error
+        );
+
+        let refused = refused.post.reject(&ana()).unwrap_err();
+        let refused = refused.post.force_publish(&bea()).unwrap_err();
+        assert_eq!(
+            "permission denied: reviewers can't force-publish posts",
+            refused.to_string()
+        );
+
+        let post = refused.post.approve(&bea()).unwrap();
+        let post = post.approve(&carla()).unwrap();
+        let refused = post.archive(&ana()).unwrap_err();
+        assert_eq!("Hello", refused.post.content());
+        assert_eq!(3, refused.post.meta().history.len());
+    }
+}
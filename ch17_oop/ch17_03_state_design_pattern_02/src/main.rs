@@ -1,12 +1,30 @@
+use ch17_03_state_design_pattern_02::permissions::User;
 use ch17_03_state_design_pattern_02::Post;
 
 fn main() {
-    let mut post = Post::new();
-    
-    post.add_text("I ate salad for lunch today");
-    
-    let post = post.request_review();
-    
-    let post = post.approve();
-    assert_eq!("I ate salad for lunch today", post.content());
+    let ana = User::author("ana");
+    let bea = User::reviewer("bea");
+    let carla = User::reviewer("carla");
+
+    let mut post = Post::with_author(ana.name());
+
+    post.add_text(&ana, "I ate salad for lunch today").unwrap();
+
+    // Un revisor lo rechaza y vuelve a ser un borrador
+    let mut post = post.request_review(&ana).unwrap().reject(&bea).unwrap();
+    post.add_text(&ana, " with my friends").unwrap();
+
+    let post = post.request_review(&ana).unwrap();
+
+    // Solo los revisores aprueban: el post vuelve dentro del error
+    let refused = post.approve(&ana).unwrap_err();
+    println!("{}", refused);
+
+    // Hacen falta dos aprobaciones
+    let post = refused.post.approve(&bea).unwrap();
+    let post = post.approve(&carla).unwrap();
+    assert_eq!(
+        "I ate salad for lunch today with my friends",
+        post.content()
+    );
 }
@@ -0,0 +1,163 @@
+use std::fmt;
+
+use crate::Action;
+
+/* Lo que un usuario puede hacer depende de su papel y, para algunas acciones,
+ * de si el post es suyo:
+ *
+ *   add_text, request_review   el autor del post o un admin
+ *   approve                    un revisor que no sea el autor, una sola vez
+ *   reject                     un revisor o un admin
+ *   force_publish, archive     solo un admin
+ *
+ * Qué transiciones existen en cada etapa lo decide el compilador, pero quién
+ * las hace solo se sabe al ejecutar. Por eso las transiciones devuelven un
+ * Result, y si se rechazan devuelven el post tal cual dentro de Refused.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Author,
+    Reviewer,
+    Admin,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Role::Author => "author",
+            Role::Reviewer => "reviewer",
+            Role::Admin => "admin",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Someone working on the blog.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_02::permissions::{PermissionError, User};
+/// use ch17_03_state_design_pattern_02::{Action, Post};
+///
+/// let bea = User::reviewer("bea");
+/// let post = Post::with_author(bea.name()).request_review(&bea).unwrap();
+///
+/// let refused = post.approve(&bea).unwrap_err();
+/// assert_eq!(PermissionError::OwnPost { action: Action::Approve }, refused.error);
+///
+/// let post = refused.post.approve(&User::reviewer("carla")).unwrap();
+/// assert_eq!(1, post.approvals());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct User {
+    name: String,
+    role: Role,
+}
+
+impl User {
+    pub fn new(name: &str, role: Role) -> User {
+        User {
+            name: String::from(name),
+            role,
+        }
+    }
+
+    pub fn author(name: &str) -> User {
+        User::new(name, Role::Author)
+    }
+
+    pub fn reviewer(name: &str) -> User {
+        User::new(name, Role::Reviewer)
+    }
+
+    pub fn admin(name: &str) -> User {
+        User::new(name, Role::Admin)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn role(&self) -> Role {
+        self.role
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionError {
+    /// Users with that role can't do the action.
+    RoleNotAllowed { action: Action, role: Role },
+    /// Only the author of the post, or an admin, can do the action.
+    NotTheAuthor { action: Action },
+    /// Nobody can do the action to their own post.
+    OwnPost { action: Action },
+    /// The reviewer already approved the post since it was sent to review.
+    AlreadyApproved,
+}
+
+impl fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PermissionError::RoleNotAllowed { action, role } => {
+                write!(f, "{}s can't {} posts", role, action)
+            }
+            PermissionError::NotTheAuthor { action } => {
+                write!(f, "only the author can {} this post", action)
+            }
+            PermissionError::OwnPost { action } => {
+                write!(f, "nobody can {} their own post", action)
+            }
+            PermissionError::AlreadyApproved => {
+                write!(f, "a reviewer can only approve a post once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PermissionError {}
+
+/// A transition that the user wasn't allowed to do. It gives the post back,
+/// unchanged.
+#[derive(Debug)]
+pub struct Refused<P> {
+    pub post: P,
+    pub error: PermissionError,
+}
+
+impl<P> fmt::Display for Refused<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "permission denied: {}", self.error)
+    }
+}
+
+impl<P: fmt::Debug> std::error::Error for Refused<P> {}
+
+/// Checks whether `user` can do `action` to a post written by `author`.
+pub fn check(action: Action, user: &User, author: &str) -> Result<(), PermissionError> {
+    let own = user.name == author;
+    let role = user.role;
+
+    match action {
+        Action::AddText | Action::RequestReview => {
+            if own || role == Role::Admin {
+                Ok(())
+            } else {
+                Err(PermissionError::NotTheAuthor { action })
+            }
+        }
+        Action::Approve if role != Role::Reviewer => {
+            Err(PermissionError::RoleNotAllowed { action, role })
+        }
+        Action::Approve if own => Err(PermissionError::OwnPost { action }),
+        Action::Approve => Ok(()),
+        Action::Reject if role == Role::Author => {
+            Err(PermissionError::RoleNotAllowed { action, role })
+        }
+        Action::Reject => Ok(()),
+        Action::ForcePublish | Action::Archive if role == Role::Admin => Ok(()),
+        Action::ForcePublish | Action::Archive => {
+            Err(PermissionError::RoleNotAllowed { action, role })
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Action, ArchivedPost, DraftPost, Event, Meta, PendingReviewPost, Post, Stage,
+    REQUIRED_APPROVALS,
+};
+
+/* Cada etapa es un tipo distinto, así que para guardarlas juntas se usa el
+ * enum StoredPost, con una variante por cada tipo. Al cargar un registro se
+ * construye la variante que corresponde a su estado y aprobaciones, y luego
+ * con un match se recupera el tipo concreto para seguir trabajando con él.
+ */
+
+/// A post in any stage, as loaded from the store.
+///
+/// # Examples
+/// ```
+/// use ch17_03_state_design_pattern_02::permissions::User;
+/// use ch17_03_state_design_pattern_02::storage::StoredPost;
+/// use ch17_03_state_design_pattern_02::Post;
+///
+/// let ferris = User::author("ferris");
+/// let bea = User::reviewer("bea");
+/// let mut post = Post::with_author(ferris.name());
+/// post.add_text(&ferris, "Hello").unwrap();
+/// let post = post.request_review(&ferris).unwrap().approve(&bea).unwrap();
+/// let stored = StoredPost::from(post);
+///
+/// let carla = User::reviewer("carla");
+/// match StoredPost::from_json(&stored.to_json()).unwrap() {
+///     StoredPost::ApprovedOnce(post) => {
+///         assert_eq!("Hello", post.approve(&carla).unwrap().content())
+///     }
+///     _ => panic!("the post should have one approval"),
+/// }
+/// ```
+pub enum StoredPost {
+    Draft(DraftPost),
+    PendingReview(PendingReviewPost<0>),
+    ApprovedOnce(PendingReviewPost<1>),
+    Published(Post),
+    Archived(ArchivedPost),
+}
+
+impl From<DraftPost> for StoredPost {
+    fn from(post: DraftPost) -> StoredPost {
+        StoredPost::Draft(post)
+    }
+}
+
+impl From<PendingReviewPost<0>> for StoredPost {
+    fn from(post: PendingReviewPost<0>) -> StoredPost {
+        StoredPost::PendingReview(post)
+    }
+}
+
+impl From<PendingReviewPost<1>> for StoredPost {
+    fn from(post: PendingReviewPost<1>) -> StoredPost {
+        StoredPost::ApprovedOnce(post)
+    }
+}
+
+impl From<Post> for StoredPost {
+    fn from(post: Post) -> StoredPost {
+        StoredPost::Published(post)
+    }
+}
+
+impl From<ArchivedPost> for StoredPost {
+    fn from(post: ArchivedPost) -> StoredPost {
+        StoredPost::Archived(post)
+    }
+}
+
+/// A post as it's stored on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostRecord {
+    pub author: String,
+    pub content: String,
+    pub state: String,
+    #[serde(default)]
+    pub approvals: u32,
+    /// Who gave the approvals.
+    pub approvers: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub history: Vec<EventRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub action: String,
+    pub actor: String,
+    pub from: String,
+    pub to: String,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// The file couldn't be read or written.
+    Io(String),
+    /// The file isn't valid JSON or doesn't describe posts.
+    Parse(String),
+    UnknownState(String),
+    UnknownAction(String),
+    /// The record has data that doesn't make sense for its state.
+    Inconsistent(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "couldn't access the store: {}", e),
+            StorageError::Parse(e) => write!(f, "invalid store: {}", e),
+            StorageError::UnknownState(state) => write!(f, "unknown post state '{}'", state),
+            StorageError::UnknownAction(action) => write!(f, "unknown action '{}'", action),
+            StorageError::Inconsistent(e) => write!(f, "inconsistent post: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> StorageError {
+        StorageError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> StorageError {
+        StorageError::Parse(e.to_string())
+    }
+}
+
+impl StoredPost {
+    pub fn stage(&self) -> Stage {
+        match self {
+            StoredPost::Draft(_) => Stage::Draft,
+            StoredPost::PendingReview(_) | StoredPost::ApprovedOnce(_) => Stage::PendingReview,
+            StoredPost::Published(_) => Stage::Published,
+            StoredPost::Archived(_) => Stage::Archived,
+        }
+    }
+
+    pub fn approvals(&self) -> u32 {
+        match self {
+            StoredPost::PendingReview(post) => post.approvals(),
+            StoredPost::ApprovedOnce(post) => post.approvals(),
+            StoredPost::Draft(_) | StoredPost::Archived(_) => 0,
+            StoredPost::Published(_) => REQUIRED_APPROVALS,
+        }
+    }
+
+    pub fn meta(&self) -> &Meta {
+        match self {
+            StoredPost::Draft(post) => &post.meta,
+            StoredPost::PendingReview(post) => &post.meta,
+            StoredPost::ApprovedOnce(post) => &post.meta,
+            StoredPost::Published(post) => &post.meta,
+            StoredPost::Archived(post) => &post.meta,
+        }
+    }
+
+    fn content(&self) -> &str {
+        match self {
+            StoredPost::Draft(post) => &post.content,
+            StoredPost::PendingReview(post) => &post.content,
+            StoredPost::ApprovedOnce(post) => &post.content,
+            StoredPost::Published(post) => &post.content,
+            StoredPost::Archived(post) => &post.content,
+        }
+    }
+
+    pub fn to_record(&self) -> PostRecord {
+        let meta = self.meta();
+        PostRecord {
+            author: meta.author.clone(),
+            content: String::from(self.content()),
+            state: String::from(stage_name(self.stage())),
+            approvals: self.approvals(),
+            approvers: meta.approvers.clone(),
+            created_at: meta.created_at,
+            updated_at: meta.updated_at,
+            history: meta
+                .history
+                .iter()
+                .map(|event| EventRecord {
+                    action: String::from(action_name(event.action)),
+                    actor: event.actor.clone(),
+                    from: String::from(stage_name(event.from)),
+                    to: String::from(stage_name(event.to)),
+                    at: event.at,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_record(record: PostRecord) -> Result<StoredPost, StorageError> {
+        let stage = parse_stage(&record.state)?;
+        let history = record
+            .history
+            .iter()
+            .map(|event| {
+                Ok(Event {
+                    action: parse_action(&event.action)?,
+                    actor: event.actor.clone(),
+                    from: parse_stage(&event.from)?,
+                    to: parse_stage(&event.to)?,
+                    at: event.at,
+                })
+            })
+            .collect::<Result<Vec<Event>, StorageError>>()?;
+
+        let approvers = approvers_for(&record)?;
+        let content = record.content;
+        let meta = Meta {
+            author: record.author,
+            created_at: record.created_at,
+            updated_at: record.updated_at,
+            history,
+            approvers,
+        };
+
+        match (stage, record.approvals) {
+            (Stage::Draft, 0) => Ok(StoredPost::Draft(DraftPost { content, meta })),
+            (Stage::PendingReview, 0) => Ok(StoredPost::PendingReview(PendingReviewPost {
+                content,
+                meta,
+            })),
+            (Stage::PendingReview, 1) => Ok(StoredPost::ApprovedOnce(PendingReviewPost {
+                content,
+                meta,
+            })),
+            (Stage::Published, REQUIRED_APPROVALS) => {
+                Ok(StoredPost::Published(Post { content, meta }))
+            }
+            (Stage::Archived, 0) => Ok(StoredPost::Archived(ArchivedPost { content, meta })),
+            (stage, approvals) => Err(StorageError::Inconsistent(format!(
+                "a post in stage '{}' can't have {} approvals",
+                stage_name(stage),
+                approvals
+            ))),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_record()).expect("records are always valid JSON")
+    }
+
+    pub fn from_json(json: &str) -> Result<StoredPost, StorageError> {
+        StoredPost::from_record(serde_json::from_str(json)?)
+    }
+}
+
+/// Keeps posts in a JSON file.
+#[derive(Debug, Clone)]
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> JsonStore {
+        JsonStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Replaces the posts in the file. It's written to a temporary file
+    /// first, so a failure never leaves the store half written.
+    pub fn save(&self, posts: &[StoredPost]) -> Result<(), StorageError> {
+        let records: Vec<PostRecord> = posts.iter().map(StoredPost::to_record).collect();
+        let json = serde_json::to_string_pretty(&records)?;
+
+        let temporary = self.path.with_extension("json.tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    /// Loads every post. A store that was never saved is empty.
+    pub fn load(&self) -> Result<Vec<StoredPost>, StorageError> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let records: Vec<PostRecord> = serde_json::from_str(&json)?;
+        records.into_iter().map(StoredPost::from_record).collect()
+    }
+}
+
+/* Cada aprobación tiene que ser de un revisor distinto. Un post publicado a
+ * la fuerza cuenta como aprobado sin que nadie lo haya hecho, así que solo se
+ * comprueba que cuadren en los posts en revisión.
+ */
+fn approvers_for(record: &PostRecord) -> Result<Vec<String>, StorageError> {
+    let approvers = record.approvers.clone();
+
+    if record.state == stage_name(Stage::PendingReview)
+        && approvers.len() != record.approvals as usize
+    {
+        return Err(StorageError::Inconsistent(format!(
+            "{} approvals, but {} approvers",
+            record.approvals,
+            approvers.len()
+        )));
+    }
+    for (i, name) in approvers.iter().enumerate() {
+        if approvers[..i].contains(name) {
+            return Err(StorageError::Inconsistent(format!(
+                "{} approved the post twice",
+                name
+            )));
+        }
+    }
+    Ok(approvers)
+}
+
+fn stage_name(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Draft => "draft",
+        Stage::PendingReview => "pending_review",
+        Stage::Published => "published",
+        Stage::Archived => "archived",
+    }
+}
+
+fn parse_stage(name: &str) -> Result<Stage, StorageError> {
+    [
+        Stage::Draft,
+        Stage::PendingReview,
+        Stage::Published,
+        Stage::Archived,
+    ]
+    .into_iter()
+    .find(|&stage| stage_name(stage) == name)
+    .ok_or_else(|| StorageError::UnknownState(String::from(name)))
+}
+
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::AddText => "add_text",
+        Action::RequestReview => "request_review",
+        Action::Approve => "approve",
+        Action::Reject => "reject",
+        Action::ForcePublish => "force_publish",
+        Action::Archive => "archive",
+    }
+}
+
+fn parse_action(name: &str) -> Result<Action, StorageError> {
+    [
+        Action::AddText,
+        Action::RequestReview,
+        Action::Approve,
+        Action::Reject,
+        Action::ForcePublish,
+        Action::Archive,
+    ]
+    .into_iter()
+    .find(|&action| action_name(action) == name)
+    .ok_or_else(|| StorageError::UnknownAction(String::from(name)))
+}
@@ -0,0 +1,7 @@
+// Cada archivo de tests/ui es un programa que no debe compilar: el .stderr
+// guarda el error que da el compilador.
+#[test]
+fn invalid_transitions_do_not_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
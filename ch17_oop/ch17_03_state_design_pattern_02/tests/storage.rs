@@ -0,0 +1,170 @@
+use std::fs;
+
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::storage::{JsonStore, StorageError, StoredPost};
+use ch17_03_state_design_pattern_02::{Action, Post, Stage};
+
+fn posts() -> Vec<StoredPost> {
+    let ana = User::author("ana");
+    let bea = User::reviewer("bea");
+    let carla = User::reviewer("carla");
+    let dani = User::admin("dani");
+
+    let mut draft = Post::with_author("ana");
+    draft.add_text(&ana, "Work in progress").unwrap();
+
+    let mut reviewed = Post::with_author("ana");
+    reviewed.add_text(&ana, "Almost there").unwrap();
+    let reviewed = reviewed.request_review(&ana).unwrap();
+
+    let mut rejected = Post::with_author("ana");
+    rejected.add_text(&ana, "Try again").unwrap();
+    let rejected = rejected.request_review(&ana).unwrap().reject(&bea).unwrap();
+    let published = rejected.request_review(&ana).unwrap();
+
+    let mut archived = Post::with_author("ana");
+    archived.add_text(&ana, "Old news").unwrap();
+    let archived = archived.request_review(&ana).unwrap();
+    let archived = archived.force_publish(&dani).unwrap();
+
+    vec![
+        draft.into(),
+        reviewed.approve(&bea).unwrap().into(),
+        published
+            .approve(&bea)
+            .unwrap()
+            .approve(&carla)
+            .unwrap()
+            .into(),
+        archived.archive(&dani).unwrap().into(),
+    ]
+}
+
+#[test]
+fn round_trip_through_a_file() {
+    let path = std::env::temp_dir().join(format!("typestate_blog_{}.json", std::process::id()));
+    let store = JsonStore::new(&path);
+    let posts = posts();
+
+    store.save(&posts).unwrap();
+    let loaded = store.load().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(posts.len(), loaded.len());
+    for (post, loaded) in posts.iter().zip(&loaded) {
+        assert_eq!(post.to_record(), loaded.to_record());
+    }
+
+    let stages: Vec<(Stage, u32)> = loaded.iter().map(|p| (p.stage(), p.approvals())).collect();
+    assert_eq!(
+        vec![
+            (Stage::Draft, 0),
+            (Stage::PendingReview, 1),
+            (Stage::Published, 2),
+            (Stage::Archived, 0)
+        ],
+        stages
+    );
+
+    let actions: Vec<Action> = loaded[2].meta().history.iter().map(|e| e.action).collect();
+    assert_eq!(
+        vec![
+            Action::RequestReview,
+            Action::Reject,
+            Action::RequestReview,
+            Action::Approve,
+            Action::Approve
+        ],
+        actions
+    );
+    let actors: Vec<&str> = loaded[2]
+        .meta()
+        .history
+        .iter()
+        .map(|e| e.actor.as_str())
+        .collect();
+    assert_eq!(vec!["ana", "bea", "ana", "bea", "carla"], actors);
+
+    let actions: Vec<Action> = loaded[3].meta().history.iter().map(|e| e.action).collect();
+    assert_eq!(
+        vec![Action::RequestReview, Action::ForcePublish, Action::Archive],
+        actions
+    );
+}
+
+#[test]
+fn loaded_posts_keep_their_type() {
+    let json = posts().remove(0).to_json();
+    match StoredPost::from_json(&json).unwrap() {
+        StoredPost::Draft(mut post) => {
+            let ana = User::author("ana");
+            post.add_text(&ana, ", done").unwrap();
+            let post = post.request_review(&ana).unwrap();
+            let post = post.force_publish(&User::admin("dani")).unwrap();
+            assert_eq!("Work in progress, done", post.content());
+            assert_eq!("ana", post.meta().author);
+        }
+        _ => panic!("the post should be a draft"),
+    }
+}
+
+#[test]
+fn unknown_states_are_refused() {
+    let mut record = posts()[0].to_record();
+    record.state = String::from("scheduled");
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::UnknownState(state)) if state == "scheduled"
+    ));
+
+    let mut record = posts()[2].to_record();
+    record.history[1].action = String::from("veto");
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::UnknownAction(action)) if action == "veto"
+    ));
+}
+
+#[test]
+fn impossible_approvals_are_refused() {
+    let mut record = posts()[1].to_record();
+    record.approvals = 2;
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[0].to_record();
+    record.approvals = 1;
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+}
+
+#[test]
+fn approvers_are_kept() {
+    let json = posts().remove(1).to_json();
+    match StoredPost::from_json(&json).unwrap() {
+        StoredPost::ApprovedOnce(post) => {
+            assert_eq!(vec!["bea"], post.meta().approvers);
+            let refused = post.approve(&User::reviewer("bea")).unwrap_err();
+            assert!(refused.post.approve(&User::reviewer("carla")).is_ok());
+        }
+        _ => panic!("the post should have one approval"),
+    }
+
+    let mut record = posts()[1].to_record();
+    record.approvers.clear();
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+
+    let mut record = posts()[1].to_record();
+    record.approvers.push(String::from("bea"));
+    assert!(matches!(
+        StoredPost::from_record(record),
+        Err(StorageError::Inconsistent(_))
+    ));
+}
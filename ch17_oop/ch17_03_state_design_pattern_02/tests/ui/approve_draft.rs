@@ -0,0 +1,7 @@
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::Post;
+
+fn main() {
+    let post = Post::new();
+    post.approve(&User::reviewer("bea"));
+}
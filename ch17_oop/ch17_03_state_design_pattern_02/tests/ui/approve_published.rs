@@ -0,0 +1,10 @@
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::Post;
+
+fn main() {
+    let ana = User::author("ana");
+    let dani = User::admin("dani");
+    let review = Post::with_author("ana").request_review(&ana).unwrap();
+    let post = review.force_publish(&dani).unwrap();
+    post.approve(&User::reviewer("bea"));
+}
@@ -0,0 +1,8 @@
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::Post;
+
+fn main() {
+    let ana = User::author("ana");
+    let post = Post::with_author("ana").request_review(&ana).unwrap();
+    post.archive(&User::admin("dani"));
+}
@@ -0,0 +1,10 @@
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::Post;
+
+fn main() {
+    let ana = User::author("ana");
+    let dani = User::admin("dani");
+    let post = Post::with_author("ana").request_review(&ana).unwrap();
+    let post = post.force_publish(&dani).unwrap().archive(&dani).unwrap();
+    post.content();
+}
@@ -0,0 +1,9 @@
+use ch17_03_state_design_pattern_02::permissions::User;
+use ch17_03_state_design_pattern_02::Post;
+
+fn main() {
+    let ana = User::author("ana");
+    let draft = Post::with_author("ana");
+    let _review = draft.request_review(&ana);
+    draft.request_review(&ana);
+}